@@ -2,20 +2,75 @@
 
 use ink_lang as ink;
 
+pub use self::pokenft::{Error, PokeNFT, PokeNftInterface, PokemonId, Seed};
+
 #[ink::contract]
 mod pokenft {
     extern crate alloc;
 
     use alloc::vec::Vec;
+    use ink_env::hash::Blake2x256;
     use ink_prelude::format;
+    use ink_prelude::string::String;
     use ink_storage::collections::{hashmap::Entry, HashMap};
+    use ink_storage::traits::{PackedLayout, SpreadLayout};
 
     #[ink(storage)]
     pub struct PokeNFT {
+        admin: AccountId,
         seeds: HashMap<Seed, (AccountId, PokemonId)>,
         owners: HashMap<AccountId, Vec<Seed>>,
         approved: HashMap<Seed, AccountId>,
         operators: HashMap<(AccountId, AccountId), bool>,
+        breeding_cooldowns: HashMap<Seed, BlockNumber>,
+        eggs: HashMap<Seed, Egg>,
+        listings: HashMap<Seed, Listing>,
+        auctions: HashMap<Seed, Auction>,
+        trades: HashMap<TradeId, Trade>,
+        next_trade_id: TradeId,
+        royalty_receiver: AccountId,
+        royalty: BasisPoints,
+        royalty_cap: BasisPoints,
+        species_royalties: HashMap<PokemonId, (AccountId, BasisPoints)>,
+        max_batch_size: u32,
+        minters: HashMap<AccountId, bool>,
+        voucher_signer: Option<AccountId>,
+        redeemed_vouchers: HashMap<u64, bool>,
+        mint_limits: MintLimits,
+        account_mints: HashMap<AccountId, u32>,
+        window_mints: HashMap<AccountId, (BlockNumber, u32)>,
+        block_mints: (BlockNumber, u32),
+        mint_phase: MintPhase,
+        presale_root: Option<merkle::Node>,
+        presale_mints: HashMap<AccountId, u32>,
+        pack_prices: HashMap<PackType, Balance>,
+        pack_nonces: HashMap<AccountId, u64>,
+        pity: HashMap<AccountId, u32>,
+        pity_threshold: u32,
+        pokedexes: HashMap<AccountId, pokedex::Pokedex>,
+        badges: HashMap<BadgeId, String>,
+        account_badges: HashMap<AccountId, Vec<BadgeId>>,
+        milestone_badges: HashMap<Milestone, BadgeId>,
+        challenges: HashMap<ChallengeId, Challenge>,
+        next_challenge_id: ChallengeId,
+        season: SeasonId,
+        season_active: bool,
+        ratings: HashMap<(SeasonId, AccountId), Rating>,
+        season_players: HashMap<SeasonId, Vec<AccountId>>,
+        standings: HashMap<SeasonId, Vec<(AccountId, Rating)>>,
+        token_ratings: HashMap<Seed, Rating>,
+        tournaments: HashMap<TournamentId, Tournament>,
+        next_tournament_id: TournamentId,
+        experience: HashMap<Seed, u32>,
+        daycare: HashMap<Seed, DaycareStay>,
+        daycare_fee: Balance,
+        locks: HashMap<Seed, Vec<Lock>>,
+        games: HashMap<AccountId, bool>,
+        attributes: HashMap<Seed, Vec<Attribute>>,
+        users: HashMap<Seed, (AccountId, BlockNumber)>,
+        loans: HashMap<Seed, Loan>,
+        // burned seeds can never be minted again
+        burned: HashMap<Seed, bool>,
     }
 
     #[derive(Debug, scale::Encode, scale::Decode, PartialEq, Eq, Copy, Clone)]
@@ -29,11 +84,433 @@ mod pokenft {
         TokenAlreadyExists,
         ValueNotFound,
         CannotRemove,
+        IncompatiblePair,
+        BreedingCooldown,
+        EggNotFound,
+        EggNotReady,
+        NotListed,
+        InvalidPrice,
+        TransferFailed,
+        InvalidDeadline,
+        AuctionNotFound,
+        AuctionNotEnded,
+        AuctionEnded,
+        BidTooLow,
+        HasBids,
+        InvalidTrade,
+        TradeNotFound,
+        TradeExpired,
+        RoyaltyTooHigh,
+        BatchTooLarge,
+        InvalidSignature,
+        VoucherExpired,
+        VoucherAlreadyRedeemed,
+        RateLimited,
+        MintingClosed,
+        InvalidProof,
+        QuotaExceeded,
+        PackUnavailable,
+        BadgeNotFound,
+        BadgeAlreadyExists,
+        BadgeAlreadyAwarded,
+        InvalidOpponent,
+        ChallengeNotFound,
+        ChallengeExpired,
+        TokenLocked,
+        NoActiveSeason,
+        SeasonInProgress,
+        TournamentNotFound,
+        TournamentFull,
+        TournamentStarted,
+        TournamentFinished,
+        NotInDaycare,
+        LockNotFound,
+        LoanNotFound,
+        LoanFunded,
+        LoanNotFunded,
+        LoanDefaulted,
+        LoanNotDefaulted,
+        DuplicateSeed,
+        ChallengeAccepted,
+        ChallengeNotAccepted,
+        InvalidReveal,
+        TokenBurned,
+        AttributeTooLarge,
+        TooManyAttributes,
+        LoanExists,
+        LoanTermsChanged,
+    }
+
+    // error for a batch message, pointing at the first item that failed. `index` is `None` when
+    // the batch was rejected as a whole, e.g. because the caller isn't allowed to send it
+    #[derive(Debug, scale::Encode, scale::Decode, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct BatchError {
+        pub index: Option<u32>,
+        pub error: Error,
     }
 
     pub type Seed = [u8; 32];
     pub type PokemonId = u32;
+    pub type TradeId = u64;
+    pub type BasisPoints = u16;
+    pub type BadgeId = u32;
+    pub type ChallengeId = u64;
+    pub type SeasonId = u32;
+    pub type Rating = u32;
+    pub type TournamentId = u64;
     pub type Result<T> = core::result::Result<T, Error>;
+    pub type BatchResult<T> = core::result::Result<T, BatchError>;
+
+    // what other contracts need to hold and move tokens. with the `ink-as-dependency` feature,
+    // `PokeNFT` becomes a reference to a deployed instance that implements it
+    #[ink::trait_definition]
+    pub trait PokeNftInterface {
+        #[ink(message)]
+        fn owner_of(&self, seed: Seed) -> AccountId;
+
+        #[ink(message)]
+        fn pokemon_of(&self, seed: Seed) -> PokemonId;
+
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, seed: Seed) -> Result<()>;
+    }
+
+    // number of blocks a parent needs to rest before breeding again
+    pub const BREEDING_COOLDOWN: BlockNumber = 600;
+    // number of blocks between breeding and an egg being ready to hatch
+    pub const HATCH_DELAY: BlockNumber = 300;
+    // bids this close to the end of an auction move the end to this many blocks after the bid
+    pub const ANTI_SNIPING_WINDOW: BlockNumber = 20;
+    pub const MAX_BASIS_POINTS: BasisPoints = 10_000;
+    // signers sign the hash of `(VOUCHER_DOMAIN, contract, voucher)`, so a voucher can only be
+    // redeemed on the deployment it was issued for
+    pub const VOUCHER_DOMAIN: &[u8] = b"pokenft:voucher";
+    // number of blocks an opponent has to accept a challenge
+    pub const CHALLENGE_TIMEOUT: BlockNumber = 1_200;
+    // number of blocks the challenger has to reveal their secret once a challenge is accepted
+    pub const CHALLENGE_REVEAL_TIMEOUT: BlockNumber = 100;
+    // number of top places announced for rewards when a season ends
+    pub const SEASON_REWARD_PLACES: u32 = 10;
+    // experience a pokemon gains for every block spent in the daycare, up to the cap per stay
+    pub const DAYCARE_EXPERIENCE_PER_BLOCK: u32 = 1;
+    pub const DAYCARE_EXPERIENCE_CAP: u32 = 5_000;
+    // furthest ahead a lock placed through `lock` can run, about a week of 6 second blocks
+    pub const MAX_LOCK_DURATION: BlockNumber = 100_800;
+    // bounds on what a game can store on a token, per game
+    pub const MAX_ATTRIBUTE_KEY_LENGTH: u32 = 32;
+    pub const MAX_ATTRIBUTE_VALUE_LENGTH: u32 = 256;
+    pub const MAX_ATTRIBUTES_PER_GAME: u32 = 16;
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Egg {
+        pub owner: AccountId,
+        pub pokemon: PokemonId,
+        pub hatches_at: BlockNumber,
+    }
+
+    // a mint authorized off-chain by the voucher signer. if `pokemon` is set it overrides the
+    // species that would be sampled from the seed
+    #[derive(Debug, scale::Encode, scale::Decode, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Voucher {
+        pub recipient: AccountId,
+        pub seed: Seed,
+        pub pokemon: Option<PokemonId>,
+        pub expires_at: BlockNumber,
+        pub nonce: u64,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum MintPhase {
+        Closed,
+        // only allowlisted accounts can mint, through `presale_mint`
+        Presale,
+        Public,
+    }
+
+    #[derive(
+        Debug,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+        PartialEq,
+        Eq,
+        PartialOrd,
+        Ord,
+        Copy,
+        Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum PackType {
+        Basic,
+        Great,
+        Legendary,
+    }
+
+    impl PackType {
+        // number of pokemon in the pack
+        pub fn size(&self) -> u32 {
+            match self {
+                PackType::Basic => 3,
+                PackType::Great => 5,
+                PackType::Legendary => 5,
+            }
+        }
+
+        // multiplier applied to the weights of rare pokemon
+        pub fn rare_boost(&self) -> u32 {
+            match self {
+                PackType::Basic => 1,
+                PackType::Great => 5,
+                PackType::Legendary => 100,
+            }
+        }
+    }
+
+    #[derive(
+        Debug,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+        PartialEq,
+        Eq,
+        PartialOrd,
+        Ord,
+        Copy,
+        Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum Milestone {
+        FirstLegendary,
+        AllStarters,
+        Complete,
+    }
+
+    // limits on public minting. `None` means unlimited
+    #[derive(
+        Debug,
+        Default,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+        PartialEq,
+        Eq,
+        Copy,
+        Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct MintLimits {
+        // mints per account, ever
+        pub per_account: Option<u32>,
+        // mints per account within each window of the given number of blocks
+        pub per_window: Option<(u32, BlockNumber)>,
+        // mints per block, across all accounts
+        pub per_block: Option<u32>,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Listing {
+        pub seller: AccountId,
+        pub price: Balance,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Auction {
+        pub seller: AccountId,
+        pub reserve: Balance,
+        pub end_block: BlockNumber,
+        pub highest_bid: Option<(AccountId, Balance)>,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Trade {
+        pub proposer: AccountId,
+        pub counterparty: AccountId,
+        pub offered: Vec<Seed>,
+        pub requested: Vec<Seed>,
+        pub expiry: BlockNumber,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Challenge {
+        pub challenger: AccountId,
+        pub challenger_seed: Seed,
+        pub opponent: AccountId,
+        pub opponent_seed: Seed,
+        pub stake: Balance,
+        pub expiry: BlockNumber,
+        // blake2x256 hash of the challenger's secret
+        pub commitment: [u8; 32],
+        // set once the opponent accepts
+        pub opponent_entropy: Option<[u8; 32]>,
+        // last block the challenger can reveal in, 0 until accepted
+        pub reveal_by: BlockNumber,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Tournament {
+        pub entry_fee: Balance,
+        pub max_entrants: u32,
+        pub prize_pool: Balance,
+        // 0 while registration is open
+        pub round: u32,
+        // entrants still in the running, in registration order
+        pub entrants: Vec<Seed>,
+        // who registered each entrant. the prize goes to them, even if the token was rented
+        pub registrants: Vec<(Seed, AccountId)>,
+        pub winner: Option<Seed>,
+        pub finished: bool,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum LockReason {
+        Challenge,
+        Tournament,
+        // for locks placed from outside the contract, tagged however the locker sees fit
+        Custom(u32),
+    }
+
+    // keeps a token from being transferred, approved or burned until `until`, or until whoever
+    // placed it removes it. locks placed by the contract's own features have it as their locker
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Lock {
+        pub reason: LockReason,
+        pub locker: AccountId,
+        pub until: BlockNumber,
+    }
+
+    // token state written by a game contract. each game only writes within its own namespace,
+    // which is its account
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Attribute {
+        pub namespace: AccountId,
+        pub key: String,
+        pub value: Vec<u8>,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct DaycareStay {
+        pub owner: AccountId,
+        pub since: BlockNumber,
+    }
+
+    #[derive(
+        Debug, scale::Encode, scale::Decode, SpreadLayout, PackedLayout, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Loan {
+        pub borrower: AccountId,
+        // `None` while the loan is only on offer
+        pub lender: Option<AccountId>,
+        pub principal: Balance,
+        pub interest: Balance,
+        pub duration: BlockNumber,
+        // last block the loan can be repaid in, 0 until it's funded
+        pub due: BlockNumber,
+    }
+
+    // Gen-1 style stats, derived from base stats, IVs and level
+    #[derive(Debug, scale::Encode, scale::Decode, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Stats {
+        pub hp: u32,
+        pub attack: u32,
+        pub defense: u32,
+        pub speed: u32,
+        pub special: u32,
+    }
+
+    #[derive(Debug, scale::Encode, scale::Decode, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct BattleOutcome {
+        pub attacker: Seed,
+        pub defender: Seed,
+        pub winner: Seed,
+        pub turns: u32,
+        pub log_hash: [u8; 32],
+    }
 
     #[ink(event)]
     pub struct Minted {
@@ -54,6 +531,320 @@ mod pokenft {
         account: Option<AccountId>,
     }
 
+    #[ink(event)]
+    pub struct Bred {
+        parent_a: Seed,
+        parent_b: Seed,
+        egg: Seed,
+        hatches_at: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct Hatched {
+        seed: Seed,
+        pokemon: PokemonId,
+    }
+
+    #[ink(event)]
+    pub struct Listed {
+        seed: Seed,
+        seller: AccountId,
+        price: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Delisted {
+        seed: Seed,
+    }
+
+    #[ink(event)]
+    pub struct Sold {
+        seed: Seed,
+        seller: AccountId,
+        buyer: AccountId,
+        price: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AuctionCreated {
+        seed: Seed,
+        seller: AccountId,
+        reserve: Balance,
+        end_block: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct BidPlaced {
+        seed: Seed,
+        bidder: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AuctionExtended {
+        seed: Seed,
+        end_block: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct AuctionSettled {
+        seed: Seed,
+        winner: Option<AccountId>,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AuctionCancelled {
+        seed: Seed,
+    }
+
+    #[ink(event)]
+    pub struct TradeProposed {
+        id: TradeId,
+        proposer: AccountId,
+        counterparty: AccountId,
+        offered: Vec<Seed>,
+        requested: Vec<Seed>,
+        expiry: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct TradeAccepted {
+        id: TradeId,
+    }
+
+    #[ink(event)]
+    pub struct TradeCancelled {
+        id: TradeId,
+    }
+
+    #[ink(event)]
+    pub struct PackOpened {
+        owner: AccountId,
+        pack_type: PackType,
+        seeds: Vec<Seed>,
+        pokemon: Vec<PokemonId>,
+    }
+
+    #[ink(event)]
+    pub struct Achievement {
+        account: AccountId,
+        milestone: Milestone,
+    }
+
+    #[ink(event)]
+    pub struct BadgeAwarded {
+        account: AccountId,
+        badge: BadgeId,
+    }
+
+    #[ink(event)]
+    pub struct MintPhaseChanged {
+        phase: MintPhase,
+    }
+
+    #[ink(event)]
+    pub struct VoucherRedeemed {
+        nonce: u64,
+        recipient: AccountId,
+        seed: Seed,
+    }
+
+    #[ink(event)]
+    pub struct RoyaltyPaid {
+        seed: Seed,
+        receiver: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BattleResult {
+        attacker: Seed,
+        defender: Seed,
+        winner: Seed,
+        turns: u32,
+        log_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct LevelUp {
+        seed: Seed,
+        level: u8,
+    }
+
+    #[ink(event)]
+    pub struct DaycareDeposited {
+        seed: Seed,
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DaycareWithdrawn {
+        seed: Seed,
+        owner: AccountId,
+        experience: u32,
+        fee: Balance,
+    }
+
+    #[ink(event)]
+    pub struct LoanOffered {
+        seed: Seed,
+        borrower: AccountId,
+        principal: Balance,
+        interest: Balance,
+        duration: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct LoanCancelled {
+        seed: Seed,
+    }
+
+    #[ink(event)]
+    pub struct LoanFunded {
+        seed: Seed,
+        lender: AccountId,
+        due: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct LoanRepaid {
+        seed: Seed,
+        borrower: AccountId,
+        lender: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct CollateralClaimed {
+        seed: Seed,
+        lender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Locked {
+        seed: Seed,
+        reason: LockReason,
+        locker: AccountId,
+        until: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct Unlocked {
+        seed: Seed,
+        reason: LockReason,
+        locker: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AttributeSet {
+        seed: Seed,
+        namespace: AccountId,
+        key: String,
+        value: Vec<u8>,
+    }
+
+    // ERC-4907. `user` is `None` when the user right is cleared
+    #[ink(event)]
+    pub struct UpdateUser {
+        seed: Seed,
+        user: Option<AccountId>,
+        expires: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct ChallengeCreated {
+        id: ChallengeId,
+        challenger: AccountId,
+        opponent: AccountId,
+        stake: Balance,
+        expiry: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct ChallengeAccepted {
+        id: ChallengeId,
+        reveal_by: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct ChallengeSettled {
+        id: ChallengeId,
+        winner: AccountId,
+        payout: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ChallengeCancelled {
+        id: ChallengeId,
+    }
+
+    #[ink(event)]
+    pub struct TournamentCreated {
+        id: TournamentId,
+        entry_fee: Balance,
+        max_entrants: u32,
+    }
+
+    #[ink(event)]
+    pub struct TournamentEntered {
+        id: TournamentId,
+        owner: AccountId,
+        seed: Seed,
+    }
+
+    #[ink(event)]
+    pub struct RoundAdvanced {
+        id: TournamentId,
+        round: u32,
+        remaining: Vec<Seed>,
+    }
+
+    #[ink(event)]
+    pub struct TournamentWon {
+        id: TournamentId,
+        winner: Option<Seed>,
+        prize: Balance,
+    }
+
+    #[ink(event)]
+    pub struct SeasonStarted {
+        season: SeasonId,
+    }
+
+    #[ink(event)]
+    pub struct SeasonEnded {
+        season: SeasonId,
+    }
+
+    #[ink(event)]
+    pub struct SeasonReward {
+        season: SeasonId,
+        rank: u32,
+        account: AccountId,
+        rating: Rating,
+    }
+
+    impl BatchError {
+        fn at(index: usize, error: Error) -> Self {
+            Self {
+                index: Some(index as u32),
+                error,
+            }
+        }
+
+        fn batch(error: Error) -> Self {
+            Self { index: None, error }
+        }
+    }
+
+    fn has_duplicates(seeds: &[Seed]) -> bool {
+        seeds
+            .iter()
+            .enumerate()
+            .any(|(idx, seed)| seeds[idx + 1..].contains(seed))
+    }
+
     mod rng {
         use rand::prelude::*;
         use rand_chacha::ChaChaRng;
@@ -93,31 +884,719 @@ mod pokenft {
 
             Ok(result as u32)
         }
-    }
 
-    impl PokeNFT {
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            Self {
-                seeds: Default::default(),
-                owners: Default::default(),
-                approved: Default::default(),
-                operators: Default::default(),
-            }
+        // pokemon this scarce or scarcer are considered rare
+        pub const RARE_POPULATION: u32 = 1_000;
+
+        pub fn is_rare(pokemon: u32) -> bool {
+            pokemon >= 1
+                && pokemon as usize <= POKEMON_LIST.len()
+                && POKEMON_LIST[(pokemon - 1) as usize] < RARE_POPULATION
         }
 
-        #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> u32 {
-            self.owners.get(&owner).map(|s| s.len() as u32).unwrap_or(0)
+        // like `sample`, but with each population re-weighted by `weight`
+        pub fn sample_weighted<F>(seed: super::Seed, weight: F) -> super::Result<u32>
+        where
+            F: Fn(u32) -> u32,
+        {
+            let total: u32 = POKEMON_LIST
+                .iter()
+                .map(|population| weight(*population))
+                .sum();
+
+            if total == 0 {
+                return Err(super::Error::InvalidSeed);
+            }
+
+            let mut rng = ChaChaRng::from_seed(seed);
+            let mut r: u32 = rng.gen_range(0..total);
+
+            for (idx, population) in POKEMON_LIST.iter().enumerate() {
+                let weight = weight(*population);
+
+                if r < weight {
+                    return Ok(idx as u32 + 1);
+                }
+
+                r -= weight;
+            }
+
+            Err(super::Error::InvalidSeed)
         }
 
-        #[ink(message)]
-        pub fn tokens_of(&self, owner: AccountId) -> Vec<Seed> {
-            self.owners.get(&owner).cloned().unwrap_or(Vec::new())
+        // Gen-1 DVs (attack, defense, speed, special), each in 0..16.
+        // the first two bytes of a seed hold them, so they can be inherited through breeding
+        pub fn ivs(seed: super::Seed) -> [u8; 4] {
+            [seed[0] >> 4, seed[0] & 0x0f, seed[1] >> 4, seed[1] & 0x0f]
         }
+    }
 
-        #[ink(message)]
-        pub fn owner_of(&self, seed: Seed) -> AccountId {
+    // deterministic Gen-1 style battles. all randomness comes from the seed passed to `resolve`
+    mod battle {
+        use super::{PokemonId, Seed, Stats};
+        use ink_env::hash::Blake2x256;
+        use rand::prelude::*;
+        use rand_chacha::ChaChaRng;
+
+        pub const MAX_LEVEL: u8 = 100;
+        // casual battles are fought at this level whatever experience the pokemon have
+        pub const CASUAL_LEVEL: u8 = 50;
+        // experience yielded by a defeated pokemon at level 7, around the Gen-1 average
+        pub const BASE_EXPERIENCE: u32 = 64;
+        // battles still going after this many turns are decided on remaining health
+        pub const MAX_TURNS: u32 = 100;
+
+        #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+        pub enum Type {
+            Normal,
+            Fire,
+            Water,
+            Electric,
+            Grass,
+            Ice,
+            Fighting,
+            Poison,
+            Ground,
+            Flying,
+            Psychic,
+            Bug,
+            Rock,
+            Ghost,
+            Dragon,
+        }
+
+        use Type::*;
+
+        // indexes are pokemon IDs - 1. values are base HP, attack, defense, speed and special,
+        // followed by the pokemon's types
+        const SPECIES: [([u8; 5], Type, Option<Type>); 151] = [
+            ([45, 49, 49, 45, 65], Grass, Some(Poison)),
+            ([60, 62, 63, 60, 80], Grass, Some(Poison)),
+            ([80, 82, 83, 80, 100], Grass, Some(Poison)),
+            ([39, 52, 43, 65, 50], Fire, None),
+            ([58, 64, 58, 80, 65], Fire, None),
+            ([78, 84, 78, 100, 85], Fire, Some(Flying)),
+            ([44, 48, 65, 43, 50], Water, None),
+            ([59, 63, 80, 58, 65], Water, None),
+            ([79, 83, 100, 78, 85], Water, None),
+            ([45, 30, 35, 45, 20], Bug, None),
+            ([50, 20, 55, 30, 25], Bug, None),
+            ([60, 45, 50, 70, 80], Bug, Some(Flying)),
+            ([40, 35, 30, 50, 20], Bug, Some(Poison)),
+            ([45, 25, 50, 35, 25], Bug, Some(Poison)),
+            ([65, 80, 40, 75, 45], Bug, Some(Poison)),
+            ([40, 45, 40, 56, 35], Normal, Some(Flying)),
+            ([63, 60, 55, 71, 50], Normal, Some(Flying)),
+            ([83, 80, 75, 91, 70], Normal, Some(Flying)),
+            ([30, 56, 35, 72, 25], Normal, None),
+            ([55, 81, 60, 97, 50], Normal, None),
+            ([40, 60, 30, 70, 31], Normal, Some(Flying)),
+            ([65, 90, 65, 100, 61], Normal, Some(Flying)),
+            ([35, 60, 44, 55, 40], Poison, None),
+            ([60, 85, 69, 80, 65], Poison, None),
+            ([35, 55, 30, 90, 50], Electric, None),
+            ([60, 90, 55, 100, 90], Electric, None),
+            ([50, 75, 85, 40, 30], Ground, None),
+            ([75, 100, 110, 65, 55], Ground, None),
+            ([55, 47, 52, 41, 40], Poison, None),
+            ([70, 62, 67, 56, 55], Poison, None),
+            ([90, 82, 87, 76, 75], Poison, Some(Ground)),
+            ([46, 57, 40, 50, 40], Poison, None),
+            ([61, 72, 57, 65, 55], Poison, None),
+            ([81, 92, 77, 85, 75], Poison, Some(Ground)),
+            ([70, 45, 48, 35, 60], Normal, None),
+            ([95, 70, 73, 60, 85], Normal, None),
+            ([38, 41, 40, 65, 65], Fire, None),
+            ([73, 76, 75, 100, 100], Fire, None),
+            ([115, 45, 20, 20, 25], Normal, None),
+            ([140, 70, 45, 45, 50], Normal, None),
+            ([40, 45, 35, 55, 40], Poison, Some(Flying)),
+            ([75, 80, 70, 90, 75], Poison, Some(Flying)),
+            ([45, 50, 55, 30, 75], Grass, Some(Poison)),
+            ([60, 65, 70, 40, 85], Grass, Some(Poison)),
+            ([75, 80, 85, 50, 100], Grass, Some(Poison)),
+            ([35, 70, 55, 25, 55], Bug, Some(Grass)),
+            ([60, 95, 80, 30, 80], Bug, Some(Grass)),
+            ([60, 55, 50, 45, 40], Bug, Some(Poison)),
+            ([70, 65, 60, 90, 90], Bug, Some(Poison)),
+            ([10, 55, 25, 95, 45], Ground, None),
+            ([35, 80, 50, 120, 70], Ground, None),
+            ([40, 45, 35, 90, 40], Normal, None),
+            ([65, 70, 60, 115, 65], Normal, None),
+            ([50, 52, 48, 55, 50], Water, None),
+            ([80, 82, 78, 85, 80], Water, None),
+            ([40, 80, 35, 70, 35], Fighting, None),
+            ([65, 105, 60, 95, 60], Fighting, None),
+            ([55, 70, 45, 60, 50], Fire, None),
+            ([90, 110, 80, 95, 80], Fire, None),
+            ([40, 50, 40, 90, 40], Water, None),
+            ([65, 65, 65, 90, 50], Water, None),
+            ([90, 85, 95, 70, 70], Water, Some(Fighting)),
+            ([25, 20, 15, 90, 105], Psychic, None),
+            ([40, 35, 30, 105, 120], Psychic, None),
+            ([55, 50, 45, 120, 135], Psychic, None),
+            ([70, 80, 50, 35, 35], Fighting, None),
+            ([80, 100, 70, 45, 50], Fighting, None),
+            ([90, 130, 80, 55, 65], Fighting, None),
+            ([50, 75, 35, 40, 70], Grass, Some(Poison)),
+            ([65, 90, 50, 55, 85], Grass, Some(Poison)),
+            ([80, 105, 65, 70, 100], Grass, Some(Poison)),
+            ([40, 40, 35, 70, 100], Water, Some(Poison)),
+            ([80, 70, 65, 100, 120], Water, Some(Poison)),
+            ([40, 80, 100, 20, 30], Rock, Some(Ground)),
+            ([55, 95, 115, 35, 45], Rock, Some(Ground)),
+            ([80, 110, 130, 45, 55], Rock, Some(Ground)),
+            ([50, 85, 55, 90, 65], Fire, None),
+            ([65, 100, 70, 105, 80], Fire, None),
+            ([90, 65, 65, 15, 40], Water, Some(Psychic)),
+            ([95, 75, 110, 30, 80], Water, Some(Psychic)),
+            ([25, 35, 70, 45, 95], Electric, None),
+            ([50, 60, 95, 70, 120], Electric, None),
+            ([52, 65, 55, 60, 58], Normal, Some(Flying)),
+            ([35, 85, 45, 75, 35], Normal, Some(Flying)),
+            ([60, 110, 70, 100, 60], Normal, Some(Flying)),
+            ([65, 45, 55, 45, 70], Water, None),
+            ([90, 70, 80, 70, 95], Water, Some(Ice)),
+            ([80, 80, 50, 25, 40], Poison, None),
+            ([105, 105, 75, 50, 65], Poison, None),
+            ([30, 65, 100, 40, 45], Water, None),
+            ([50, 95, 180, 70, 85], Water, Some(Ice)),
+            ([30, 35, 30, 80, 100], Ghost, Some(Poison)),
+            ([45, 50, 45, 95, 115], Ghost, Some(Poison)),
+            ([60, 65, 60, 110, 130], Ghost, Some(Poison)),
+            ([35, 45, 160, 70, 30], Rock, Some(Ground)),
+            ([60, 48, 45, 42, 90], Psychic, None),
+            ([85, 73, 70, 67, 115], Psychic, None),
+            ([30, 105, 90, 50, 25], Water, None),
+            ([55, 130, 115, 75, 50], Water, None),
+            ([40, 30, 50, 100, 55], Electric, None),
+            ([60, 50, 70, 140, 80], Electric, None),
+            ([60, 40, 80, 40, 60], Grass, Some(Psychic)),
+            ([95, 95, 85, 55, 125], Grass, Some(Psychic)),
+            ([50, 50, 95, 35, 40], Ground, None),
+            ([60, 80, 110, 45, 50], Ground, None),
+            ([50, 120, 53, 87, 35], Fighting, None),
+            ([50, 105, 79, 76, 35], Fighting, None),
+            ([90, 55, 75, 30, 60], Normal, None),
+            ([40, 65, 95, 35, 60], Poison, None),
+            ([65, 90, 120, 60, 85], Poison, None),
+            ([80, 85, 95, 25, 30], Ground, Some(Rock)),
+            ([105, 130, 120, 40, 45], Ground, Some(Rock)),
+            ([250, 5, 5, 50, 105], Normal, None),
+            ([65, 55, 115, 60, 100], Grass, None),
+            ([105, 95, 80, 90, 40], Normal, None),
+            ([30, 40, 70, 60, 70], Water, None),
+            ([55, 65, 95, 85, 95], Water, None),
+            ([45, 67, 60, 63, 50], Water, None),
+            ([80, 92, 65, 68, 80], Water, None),
+            ([30, 45, 55, 85, 70], Water, None),
+            ([60, 75, 85, 115, 100], Water, Some(Psychic)),
+            ([40, 45, 65, 90, 100], Psychic, None),
+            ([70, 110, 80, 105, 55], Bug, Some(Flying)),
+            ([65, 50, 35, 95, 95], Ice, Some(Psychic)),
+            ([65, 83, 57, 105, 85], Electric, None),
+            ([65, 95, 57, 93, 85], Fire, None),
+            ([65, 125, 100, 85, 55], Bug, None),
+            ([75, 100, 95, 110, 70], Normal, None),
+            ([20, 10, 55, 80, 20], Water, None),
+            ([95, 125, 79, 81, 100], Water, Some(Flying)),
+            ([130, 85, 80, 60, 95], Water, Some(Ice)),
+            ([48, 48, 48, 48, 48], Normal, None),
+            ([55, 55, 50, 55, 65], Normal, None),
+            ([130, 65, 60, 65, 110], Water, None),
+            ([65, 65, 60, 130, 110], Electric, None),
+            ([65, 130, 60, 65, 110], Fire, None),
+            ([65, 60, 70, 40, 75], Normal, None),
+            ([35, 40, 100, 35, 90], Rock, Some(Water)),
+            ([70, 60, 125, 55, 115], Rock, Some(Water)),
+            ([30, 80, 90, 55, 45], Rock, Some(Water)),
+            ([60, 115, 105, 80, 70], Rock, Some(Water)),
+            ([80, 105, 65, 130, 60], Rock, Some(Flying)),
+            ([160, 110, 65, 30, 65], Normal, None),
+            ([90, 85, 100, 85, 125], Ice, Some(Flying)),
+            ([90, 90, 85, 100, 125], Electric, Some(Flying)),
+            ([90, 100, 90, 90, 125], Fire, Some(Flying)),
+            ([41, 64, 45, 50, 50], Dragon, None),
+            ([61, 84, 65, 70, 70], Dragon, None),
+            ([91, 134, 95, 80, 100], Dragon, Some(Flying)),
+            ([106, 110, 90, 130, 154], Psychic, None),
+            ([100, 100, 100, 100, 100], Psychic, None),
+        ];
+
+        // rows are attacking types, columns defending types. values are multipliers x10
+        const EFFECTIVENESS: [[u8; 15]; 15] = [
+            [10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 5, 0, 10], // Normal
+            [10, 5, 5, 10, 20, 20, 10, 10, 10, 10, 10, 20, 5, 10, 5],   // Fire
+            [10, 20, 5, 10, 5, 10, 10, 10, 20, 10, 10, 10, 20, 10, 5],  // Water
+            [10, 10, 20, 5, 5, 10, 10, 10, 0, 20, 10, 10, 10, 10, 5],   // Electric
+            [10, 5, 20, 10, 5, 10, 10, 5, 20, 5, 10, 5, 20, 10, 5],     // Grass
+            [10, 10, 5, 10, 20, 5, 10, 10, 20, 20, 10, 10, 10, 10, 20], // Ice
+            [20, 10, 10, 10, 10, 20, 10, 5, 10, 5, 5, 5, 20, 0, 10],    // Fighting
+            [10, 10, 10, 10, 20, 10, 10, 5, 5, 10, 10, 20, 5, 5, 10],   // Poison
+            [10, 20, 10, 20, 5, 10, 10, 20, 10, 0, 10, 5, 20, 10, 10],  // Ground
+            [10, 10, 10, 5, 20, 10, 20, 10, 10, 10, 10, 20, 5, 10, 10], // Flying
+            [10, 10, 10, 10, 10, 10, 20, 20, 10, 10, 5, 10, 10, 10, 10], // Psychic
+            [10, 5, 10, 10, 20, 10, 5, 20, 10, 5, 20, 10, 10, 5, 10],   // Bug
+            [10, 20, 10, 10, 10, 20, 5, 10, 5, 20, 10, 20, 10, 10, 10], // Rock
+            [0, 10, 10, 10, 10, 10, 10, 10, 10, 10, 0, 10, 10, 20, 10], // Ghost
+            [10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 20], // Dragon
+        ];
+
+        // every pokemon knows a single move per type it has. values are base power
+        const MOVES: [u32; 15] = [
+            85,  // Normal: Body Slam
+            95,  // Fire: Flamethrower
+            95,  // Water: Surf
+            95,  // Electric: Thunderbolt
+            55,  // Grass: Razor Leaf
+            95,  // Ice: Ice Beam
+            80,  // Fighting: Submission
+            65,  // Poison: Sludge
+            100, // Ground: Earthquake
+            80,  // Flying: Drill Peck
+            90,  // Psychic: Psychic
+            25,  // Bug: Twineedle
+            75,  // Rock: Rock Slide
+            20,  // Ghost: Lick
+            60,  // Dragon: Dragon Rage, which deals fixed damage in the games
+        ];
+
+        impl Type {
+            // Gen-1 decides whether a move is physical or special by its type
+            pub fn is_special(&self) -> bool {
+                matches!(
+                    self,
+                    Fire | Water | Electric | Grass | Ice | Psychic | Dragon
+                )
+            }
+        }
+
+        #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+        pub struct Combatant {
+            pub pokemon: PokemonId,
+            pub ivs: [u8; 4],
+            pub level: u8,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+        pub struct Outcome {
+            pub attacker_won: bool,
+            pub turns: u32,
+            // hash chained over every move, so the whole battle can be replayed and checked
+            pub log_hash: [u8; 32],
+        }
+
+        struct Fighter {
+            types: (Type, Option<Type>),
+            stats: Stats,
+            hp: u32,
+            level: u32,
+        }
+
+        pub fn types(pokemon: PokemonId) -> super::Result<(Type, Option<Type>)> {
+            SPECIES
+                .get((pokemon as usize).wrapping_sub(1))
+                .map(|(_, primary, secondary)| (*primary, *secondary))
+                .ok_or(super::Error::InvalidSeed)
+        }
+
+        // damage multiplier x100 of a move against a pokemon of the given types
+        pub fn effectiveness(attack: Type, defender: (Type, Option<Type>)) -> u32 {
+            let multiplier = |defending: Type| EFFECTIVENESS[attack as usize][defending as usize];
+
+            multiplier(defender.0) as u32 * defender.1.map_or(10, multiplier) as u32
+        }
+
+        // Gen-1 "medium fast" growth: reaching level n takes n^3 experience
+        pub fn experience_for(level: u8) -> u32 {
+            (level as u32).pow(3)
+        }
+
+        pub fn level(experience: u32) -> u8 {
+            let mut level = 1;
+
+            while level < MAX_LEVEL && experience_for(level + 1) <= experience {
+                level += 1;
+            }
+
+            level
+        }
+
+        // experience gained for defeating a pokemon of the given level
+        pub fn experience_yield(level: u8) -> u32 {
+            (BASE_EXPERIENCE * level as u32 / 7).max(1)
+        }
+
+        // Gen-1 stat formula, leaving out stat experience
+        pub fn stats(pokemon: PokemonId, ivs: [u8; 4], level: u8) -> super::Result<Stats> {
+            let (base, _, _) = SPECIES
+                .get((pokemon as usize).wrapping_sub(1))
+                .ok_or(super::Error::InvalidSeed)?;
+            let level = level as u32;
+            let stat = |base: u8, iv: u8| (base as u32 + iv as u32) * 2 * level / 100;
+
+            // the HP DV is made up of the lowest bit of every other DV
+            let hp_iv = (ivs[0] & 1) << 3 | (ivs[1] & 1) << 2 | (ivs[2] & 1) << 1 | (ivs[3] & 1);
+
+            Ok(Stats {
+                hp: stat(base[0], hp_iv) + level + 10,
+                attack: stat(base[1], ivs[0]) + 5,
+                defense: stat(base[2], ivs[1]) + 5,
+                speed: stat(base[3], ivs[2]) + 5,
+                special: stat(base[4], ivs[3]) + 5,
+            })
+        }
+
+        impl Fighter {
+            fn new(combatant: Combatant) -> super::Result<Self> {
+                let stats = stats(combatant.pokemon, combatant.ivs, combatant.level)?;
+
+                Ok(Self {
+                    types: types(combatant.pokemon)?,
+                    stats,
+                    hp: stats.hp,
+                    level: combatant.level as u32,
+                })
+            }
+
+            // of the moves it knows, the one that hits the opponent hardest
+            fn best_move(&self, opponent: &Fighter) -> Type {
+                let (primary, secondary) = self.types;
+                let score =
+                    |attack: Type| MOVES[attack as usize] * effectiveness(attack, opponent.types);
+
+                match secondary {
+                    Some(secondary) if score(secondary) > score(primary) => secondary,
+                    _ => primary,
+                }
+            }
+
+            fn damage(&self, opponent: &Fighter, attack: Type, rng: &mut ChaChaRng) -> u32 {
+                let (attack_stat, defense_stat) = if attack.is_special() {
+                    (self.stats.special, opponent.stats.special)
+                } else {
+                    (self.stats.attack, opponent.stats.defense)
+                };
+
+                let base = (2 * self.level / 5 + 2) * MOVES[attack as usize] * attack_stat
+                    / defense_stat.max(1)
+                    / 50
+                    + 2;
+                // moves always match one of the user's types, so they always get STAB
+                let damage = base * 3 / 2 * effectiveness(attack, opponent.types) / 100;
+
+                if damage == 0 {
+                    return 0;
+                }
+
+                // Gen-1 damage roll
+                (damage * rng.gen_range(217..=255) / 255).max(1)
+            }
+        }
+
+        pub fn resolve(
+            attacker: Combatant,
+            defender: Combatant,
+            seed: Seed,
+        ) -> super::Result<Outcome> {
+            let mut rng = ChaChaRng::from_seed(seed);
+            let mut fighters = [Fighter::new(attacker)?, Fighter::new(defender)?];
+            let moves = [
+                fighters[0].best_move(&fighters[1]),
+                fighters[1].best_move(&fighters[0]),
+            ];
+
+            // the faster pokemon moves first, speed ties are a coin flip
+            let first = match fighters[0].stats.speed.cmp(&fighters[1].stats.speed) {
+                core::cmp::Ordering::Greater => 0,
+                core::cmp::Ordering::Less => 1,
+                core::cmp::Ordering::Equal => rng.gen::<bool>() as usize,
+            };
+
+            let mut log_hash = [0u8; 32];
+
+            for turn in 1..=MAX_TURNS {
+                for actor in [first, 1 - first].iter().cloned() {
+                    let target = 1 - actor;
+                    let damage = fighters[actor].damage(&fighters[target], moves[actor], &mut rng);
+                    fighters[target].hp = fighters[target].hp.saturating_sub(damage);
+
+                    let entry = (log_hash, turn, actor as u8, damage, fighters[target].hp);
+                    ink_env::hash_encoded::<Blake2x256, _>(&entry, &mut log_hash);
+
+                    if fighters[target].hp == 0 {
+                        return Ok(Outcome {
+                            attacker_won: target == 1,
+                            turns: turn,
+                            log_hash,
+                        });
+                    }
+                }
+            }
+
+            // whoever kept the larger share of their health wins, the defender on a tie
+            let [attacker, defender] = &fighters;
+
+            Ok(Outcome {
+                attacker_won: attacker.hp * defender.stats.hp > defender.hp * attacker.stats.hp,
+                turns: MAX_TURNS,
+                log_hash,
+            })
+        }
+    }
+
+    // Elo ratings, in integer math
+    mod elo {
+        use super::Rating;
+
+        pub const INITIAL: Rating = 1_200;
+        const K: u32 = 32;
+        const STEP: u32 = 25;
+
+        // expected score in per mille for rating differences of 0, 25, 50... 800 points
+        const EXPECTED: [u32; 33] = [
+            500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909,
+            920, 930, 939, 947, 954, 960, 965, 969, 973, 977, 980, 983, 985, 987, 989, 990,
+        ];
+
+        // expected score in per mille of a player rated `rating` against one rated `opponent`
+        pub fn expected(rating: Rating, opponent: Rating) -> u32 {
+            let lookup = |diff: u32| {
+                let idx = (diff / STEP) as usize;
+
+                if idx + 1 >= EXPECTED.len() {
+                    return EXPECTED[EXPECTED.len() - 1];
+                }
+
+                // interpolate between the two closest entries
+                EXPECTED[idx] + (EXPECTED[idx + 1] - EXPECTED[idx]) * (diff % STEP) / STEP
+            };
+
+            if rating >= opponent {
+                lookup(rating - opponent)
+            } else {
+                1000 - lookup(opponent - rating)
+            }
+        }
+
+        // new ratings of the winner and the loser of a match
+        pub fn update(winner: Rating, loser: Rating) -> (Rating, Rating) {
+            let delta = (K * (1000 - expected(winner, loser)) + 500) / 1000;
+
+            (winner + delta, loser.saturating_sub(delta))
+        }
+    }
+
+    // bitset of every species an account has ever owned
+    mod pokedex {
+        use super::{Milestone, PokemonId};
+        use ink_prelude::vec::Vec;
+
+        pub type Pokedex = [u64; 3];
+
+        pub const SIZE: u32 = 151;
+        const STARTERS: [PokemonId; 3] = [1, 4, 7];
+        const LEGENDARIES: [PokemonId; 5] = [144, 145, 146, 150, 151];
+
+        pub fn register(pokedex: &mut Pokedex, pokemon: PokemonId) {
+            if pokemon >= 1 && pokemon <= SIZE {
+                let bit = pokemon - 1;
+                pokedex[(bit / 64) as usize] |= 1u64 << (bit % 64);
+            }
+        }
+
+        pub fn contains(pokedex: &Pokedex, pokemon: PokemonId) -> bool {
+            let bit = pokemon.wrapping_sub(1);
+            bit < SIZE && pokedex[(bit / 64) as usize] & (1u64 << (bit % 64)) != 0
+        }
+
+        pub fn count(pokedex: &Pokedex) -> u32 {
+            pokedex.iter().map(|word| word.count_ones()).sum()
+        }
+
+        pub fn species(pokedex: &Pokedex) -> Vec<PokemonId> {
+            (1..=SIZE).filter(|p| contains(pokedex, *p)).collect()
+        }
+
+        pub fn milestones(pokedex: &Pokedex) -> Vec<Milestone> {
+            let mut milestones = Vec::new();
+
+            if LEGENDARIES.iter().any(|p| contains(pokedex, *p)) {
+                milestones.push(Milestone::FirstLegendary);
+            }
+
+            if STARTERS.iter().all(|p| contains(pokedex, *p)) {
+                milestones.push(Milestone::AllStarters);
+            }
+
+            if count(pokedex) == SIZE {
+                milestones.push(Milestone::Complete);
+            }
+
+            milestones
+        }
+    }
+
+    // merkle trees of allowlisted accounts. pairs are hashed in sorted order, so proofs don't need
+    // to say which side each sibling is on
+    mod merkle {
+        use super::AccountId;
+        use ink_env::hash::Blake2x256;
+
+        pub type Node = [u8; 32];
+
+        pub fn leaf(account: &AccountId, quota: u32) -> Node {
+            let mut output = Node::default();
+            ink_env::hash_encoded::<Blake2x256, _>(&(account, quota), &mut output);
+            output
+        }
+
+        pub fn hash_pair(a: Node, b: Node) -> Node {
+            let pair = if a <= b { (a, b) } else { (b, a) };
+
+            let mut output = Node::default();
+            ink_env::hash_encoded::<Blake2x256, _>(&pair, &mut output);
+            output
+        }
+
+        pub fn verify(root: Node, leaf: Node, proof: &[Node]) -> bool {
+            proof
+                .iter()
+                .fold(leaf, |node, sibling| hash_pair(node, *sibling))
+                == root
+        }
+    }
+
+    mod breeding {
+        use super::{PokemonId, Seed};
+
+        const DITTO: PokemonId = 132;
+
+        // legendaries and Mew can't breed
+        const UNDISCOVERED: [PokemonId; 5] = [144, 145, 146, 150, 151];
+
+        // indexes are pokemon IDs - 1. values are the first stage of each evolution line
+        pub const BASE_FORMS: [PokemonId; 151] = [
+            1, 1, 1, 4, 4, 4, 7, 7, 7, 10, 10, 10, 13, 13, 13, 16, 16, 16, 19, 19, 21, 21, 23, 23,
+            25, 25, 27, 27, 29, 29, 29, 32, 32, 32, 35, 35, 37, 37, 39, 39, 41, 41, 43, 43, 43, 46,
+            46, 48, 48, 50, 50, 52, 52, 54, 54, 56, 56, 58, 58, 60, 60, 60, 63, 63, 63, 66, 66, 66,
+            69, 69, 69, 72, 72, 74, 74, 74, 77, 77, 79, 79, 81, 81, 83, 84, 84, 86, 86, 88, 88, 90,
+            90, 92, 92, 92, 95, 96, 96, 98, 98, 100, 100, 102, 102, 104, 104, 106, 107, 108, 109,
+            109, 111, 111, 113, 114, 115, 116, 116, 118, 118, 120, 120, 122, 123, 124, 125, 126,
+            127, 128, 129, 129, 131, 132, 133, 133, 133, 133, 137, 138, 138, 140, 140, 142, 143,
+            144, 145, 146, 147, 147, 147, 150, 151,
+        ];
+
+        pub fn base_form(pokemon: PokemonId) -> PokemonId {
+            BASE_FORMS[(pokemon - 1) as usize]
+        }
+
+        pub fn is_compatible(a: PokemonId, b: PokemonId) -> bool {
+            let valid = |p: PokemonId| p >= 1 && p as usize <= BASE_FORMS.len();
+
+            valid(a)
+                && valid(b)
+                && !UNDISCOVERED.contains(&a)
+                && !UNDISCOVERED.contains(&b)
+                && !(a == DITTO && b == DITTO)
+        }
+
+        // the egg takes the base form of one of its parents. Ditto always passes on the other one
+        pub fn offspring(a: PokemonId, b: PokemonId, entropy: Seed) -> PokemonId {
+            if a == DITTO {
+                base_form(b)
+            } else if b == DITTO || entropy[31] & 1 == 0 {
+                base_form(a)
+            } else {
+                base_form(b)
+            }
+        }
+
+        // attack/defense DVs come from parent a, speed/special DVs from parent b
+        pub fn child_seed(parent_a: Seed, parent_b: Seed, entropy: Seed) -> Seed {
+            let mut seed = entropy;
+            seed[0] = parent_a[0];
+            seed[1] = parent_b[1];
+            seed
+        }
+    }
+
+    impl PokeNFT {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let caller = Self::env().caller();
+            let mut minters = HashMap::new();
+            minters.insert(caller, true);
+
+            Self {
+                admin: caller,
+                seeds: Default::default(),
+                owners: Default::default(),
+                approved: Default::default(),
+                operators: Default::default(),
+                breeding_cooldowns: Default::default(),
+                eggs: Default::default(),
+                listings: Default::default(),
+                auctions: Default::default(),
+                trades: Default::default(),
+                next_trade_id: 0,
+                royalty_receiver: caller,
+                royalty: 0,
+                royalty_cap: 1_000,
+                species_royalties: Default::default(),
+                max_batch_size: 100,
+                minters,
+                voucher_signer: None,
+                redeemed_vouchers: Default::default(),
+                mint_limits: Default::default(),
+                account_mints: Default::default(),
+                window_mints: Default::default(),
+                block_mints: (0, 0),
+                mint_phase: MintPhase::Public,
+                presale_root: None,
+                presale_mints: Default::default(),
+                pack_prices: Default::default(),
+                pack_nonces: Default::default(),
+                pity: Default::default(),
+                pity_threshold: 200,
+                pokedexes: Default::default(),
+                badges: Default::default(),
+                account_badges: Default::default(),
+                milestone_badges: Default::default(),
+                challenges: Default::default(),
+                next_challenge_id: 0,
+                season: 0,
+                season_active: false,
+                ratings: Default::default(),
+                season_players: Default::default(),
+                standings: Default::default(),
+                token_ratings: Default::default(),
+                tournaments: Default::default(),
+                next_tournament_id: 0,
+                experience: Default::default(),
+                daycare: Default::default(),
+                daycare_fee: 0,
+                locks: Default::default(),
+                games: Default::default(),
+                attributes: Default::default(),
+                users: Default::default(),
+                loans: Default::default(),
+                burned: Default::default(),
+            }
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> u32 {
+            self.owners.get(&owner).map(|s| s.len() as u32).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn tokens_of(&self, owner: AccountId) -> Vec<Seed> {
+            self.owners.get(&owner).cloned().unwrap_or(Vec::new())
+        }
+
+        #[ink(message)]
+        pub fn owner_of(&self, seed: Seed) -> AccountId {
             self.seeds
                 .get(&seed)
                 .cloned()
@@ -135,11 +1614,15 @@ mod pokenft {
         #[ink(message)]
         pub fn transfer(&mut self, to: AccountId, seed: Seed) -> Result<()> {
             let caller = self.env().caller();
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
             self.impl_transfer_from(&caller, &to, seed)
         }
 
         #[ink(message)]
         pub fn transfer_from(&mut self, from: AccountId, to: AccountId, seed: Seed) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
             self.impl_transfer_from(&from, &to, seed)
         }
 
@@ -148,13 +1631,9 @@ mod pokenft {
             self.assert_exists(seed)?;
             self.assert_valid_account(&approved)?;
             self.assert_owner_or_approved(seed)?;
+            self.assert_unlocked(seed)?;
 
-            self.approved.insert(seed, approved);
-
-            self.env().emit_event(Approval {
-                seed,
-                account: Some(approved),
-            });
+            self.impl_approve(&approved, seed);
 
             Ok(())
         }
@@ -181,21 +1660,7 @@ mod pokenft {
         pub fn clear_approval(&mut self, seed: Seed) -> Result<()> {
             self.assert_exists(seed)?;
             self.assert_owner_or_approved(seed)?;
-
-            if !self.approved.contains_key(&seed) {
-                return Ok(());
-            }
-
-            match self.approved.take(&seed) {
-                Some(_) => {
-                    self.env().emit_event(Approval {
-                        seed,
-                        account: None,
-                    });
-                    Ok(())
-                }
-                None => Err(Error::CannotRemove),
-            }
+            self.impl_clear_approval(seed)
         }
 
         #[ink(message)]
@@ -209,479 +1674,7154 @@ mod pokenft {
         }
 
         #[ink(message)]
-        pub fn mint(&mut self, seed: Seed) -> Result<()> {
-            let owner = self.env().caller();
+        pub fn list(&mut self, seed: Seed, price: Balance) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
 
-            self.add_token_to(&owner, seed)?;
+            if price == 0 {
+                return Err(Error::InvalidPrice);
+            }
 
-            self.env().emit_event(Transfer {
-                from: None,
-                to: Some(owner),
+            let seller = self.owner_of(seed);
+            self.listings.insert(seed, Listing { seller, price });
+
+            self.env().emit_event(Listed {
                 seed,
+                seller,
+                price,
             });
 
             Ok(())
         }
 
-        fn assert_exists(&self, seed: Seed) -> Result<()> {
-            if !self.exists(seed) {
-                return Err(Error::TokenNotFound);
-            }
+        #[ink(message)]
+        pub fn delist(&mut self, seed: Seed) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
 
-            Ok(())
-        }
+            self.listings.take(&seed).ok_or(Error::NotListed)?;
 
-        fn assert_valid_account(&self, account: &AccountId) -> Result<()> {
-            if *account == AccountId::from([0x0; 32]) {
-                return Err(Error::InvalidAddress);
-            }
+            self.env().emit_event(Delisted { seed });
 
             Ok(())
         }
 
-        fn assert_owner_or_approved(&self, seed: Seed) -> Result<()> {
-            let caller = self.env().caller();
-            let owner = self.owner_of(seed);
-            let current_approver = self.approved.get(&seed);
+        #[ink(message, payable)]
+        pub fn buy(&mut self, seed: Seed) -> Result<()> {
+            let buyer = self.env().caller();
+            let Listing { seller, price } = self.listing_of(seed).ok_or(Error::NotListed)?;
 
-            if !(owner == caller
-                || current_approver == Some(&caller)
-                || self.is_approved_for_all(owner, caller))
-            {
+            if buyer == seller {
                 return Err(Error::NotAllowed);
             }
 
+            if self.env().transferred_balance() != price {
+                return Err(Error::InvalidPrice);
+            }
+
+            self.impl_transfer_from(&seller, &buyer, seed)?;
+            self.pay_sale(seed, &seller, price)?;
+
+            self.env().emit_event(Sold {
+                seed,
+                seller,
+                buyer,
+                price,
+            });
+
             Ok(())
         }
 
-        fn exists(&self, seed: Seed) -> bool {
-            self.seeds.contains_key(&seed)
+        #[ink(message)]
+        pub fn listing_of(&self, seed: Seed) -> Option<Listing> {
+            self.listings.get(&seed).cloned()
         }
 
-        fn impl_transfer_from(
+        #[ink(message)]
+        pub fn listings(&self, offset: u32, limit: u32) -> Vec<(Seed, Listing)> {
+            self.listings
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .map(|(seed, listing)| (*seed, *listing))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn create_auction(
             &mut self,
-            from: &AccountId,
-            to: &AccountId,
             seed: Seed,
+            reserve: Balance,
+            end_block: BlockNumber,
         ) -> Result<()> {
             self.assert_exists(seed)?;
             self.assert_owner_or_approved(seed)?;
-            self.assert_valid_account(to)?;
 
-            self.clear_approval(seed)?;
-            self.remove_token_from(from, seed)?;
-            self.add_token_to(to, seed)?;
+            if end_block <= self.env().block_number() {
+                return Err(Error::InvalidDeadline);
+            }
+
+            // the token is escrowed by the contract until the auction is settled or cancelled
+            let seller = self.owner_of(seed);
+            let contract = self.env().account_id();
+            self.impl_transfer_from(&seller, &contract, seed)?;
 
-            self.env().emit_event(Transfer {
-                from: Some(*from),
-                to: Some(*to),
+            self.auctions.insert(
                 seed,
+                Auction {
+                    seller,
+                    reserve,
+                    end_block,
+                    highest_bid: None,
+                },
+            );
+
+            self.env().emit_event(AuctionCreated {
+                seed,
+                seller,
+                reserve,
+                end_block,
             });
 
             Ok(())
         }
 
-        fn remove_token_from(&mut self, from: &AccountId, seed: Seed) -> Result<()> {
-            // remove entry from seeds hash
-            let seeds_entry = match self.seeds.entry(seed) {
-                Entry::Vacant(_) => return Err(Error::TokenNotFound),
-                Entry::Occupied(entry) => entry,
-            };
-            seeds_entry.remove_entry();
-
-            // remove entry from owner's vec of owned seeds
-            self.owners
-                .get_mut(from)
-                .ok_or(Error::NotOwner)?
-                .retain(|&owned_seed| owned_seed != seed);
+        #[ink(message, payable)]
+        pub fn bid(&mut self, seed: Seed) -> Result<()> {
+            let bidder = self.env().caller();
+            let amount = self.env().transferred_balance();
+            let now = self.env().block_number();
+            let mut auction = self.auction_of(seed).ok_or(Error::AuctionNotFound)?;
 
-            Ok(())
-        }
+            if now > auction.end_block {
+                return Err(Error::AuctionEnded);
+            }
 
-        fn add_token_to(&mut self, to: &AccountId, seed: Seed) -> Result<()> {
-            let id = rng::sample(seed).map_err(|_| Error::InvalidSeed)?;
+            if bidder == auction.seller {
+                return Err(Error::NotAllowed);
+            }
 
-            let seeds_entry = match self.seeds.entry(seed) {
-                Entry::Vacant(entry) => entry,
-                Entry::Occupied(_) => return Err(Error::TokenAlreadyExists),
+            let too_low = match auction.highest_bid {
+                Some((_, highest)) => amount <= highest,
+                None => amount < auction.reserve,
             };
 
-            if *to == AccountId::from([0x0; 32]) {
-                return Err(Error::NotAllowed);
+            if amount == 0 || too_low {
+                return Err(Error::BidTooLow);
             }
 
-            seeds_entry.insert((*to, id));
+            if let Some((previous_bidder, previous_amount)) = auction.highest_bid {
+                self.pay(&previous_bidder, previous_amount)?;
+            }
 
-            self.owners.entry(*to).or_insert(Vec::new()).push(seed);
+            auction.highest_bid = Some((bidder, amount));
 
-            Ok(())
+            let extended = auction.end_block - now < ANTI_SNIPING_WINDOW;
+            if extended {
+                auction.end_block = now + ANTI_SNIPING_WINDOW;
+            }
+
+            self.auctions.insert(seed, auction);
+
+            self.env().emit_event(BidPlaced {
+                seed,
+                bidder,
+                amount,
+            });
+
+            if extended {
+                self.env().emit_event(AuctionExtended {
+                    seed,
+                    end_block: auction.end_block,
+                });
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn settle(&mut self, seed: Seed) -> Result<()> {
+            let auction = self.auction_of(seed).ok_or(Error::AuctionNotFound)?;
+
+            if self.env().block_number() <= auction.end_block {
+                return Err(Error::AuctionNotEnded);
+            }
+
+            self.auctions.take(&seed);
+
+            let contract = self.env().account_id();
+
+            let (winner, amount) = match auction.highest_bid {
+                Some((bidder, amount)) => {
+                    self.impl_transfer_from(&contract, &bidder, seed)?;
+                    self.pay_sale(seed, &auction.seller, amount)?;
+                    (Some(bidder), amount)
+                }
+                None => {
+                    self.impl_transfer_from(&contract, &auction.seller, seed)?;
+                    (None, 0)
+                }
+            };
+
+            self.env().emit_event(AuctionSettled {
+                seed,
+                winner,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_auction(&mut self, seed: Seed) -> Result<()> {
+            let auction = self.auction_of(seed).ok_or(Error::AuctionNotFound)?;
+
+            if self.env().caller() != auction.seller {
+                return Err(Error::NotAllowed);
+            }
+
+            if auction.highest_bid.is_some() {
+                return Err(Error::HasBids);
+            }
+
+            self.auctions.take(&seed);
+
+            let contract = self.env().account_id();
+            self.impl_transfer_from(&contract, &auction.seller, seed)?;
+
+            self.env().emit_event(AuctionCancelled { seed });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn auction_of(&self, seed: Seed) -> Option<Auction> {
+            self.auctions.get(&seed).cloned()
+        }
+
+        #[ink(message)]
+        pub fn propose_trade(
+            &mut self,
+            offered: Vec<Seed>,
+            requested: Vec<Seed>,
+            counterparty: AccountId,
+            expiry: BlockNumber,
+        ) -> Result<TradeId> {
+            let proposer = self.env().caller();
+            self.assert_valid_account(&counterparty)?;
+
+            if counterparty == proposer
+                || offered.is_empty()
+                || requested.is_empty()
+                || has_duplicates(&[&offered[..], &requested[..]].concat())
+            {
+                return Err(Error::InvalidTrade);
+            }
+
+            if expiry <= self.env().block_number() {
+                return Err(Error::InvalidDeadline);
+            }
+
+            let trade = Trade {
+                proposer,
+                counterparty,
+                offered,
+                requested,
+                expiry,
+            };
+            self.assert_trade_ownership(&trade)?;
+
+            let id = self.next_trade_id;
+            self.next_trade_id += 1;
+
+            self.env().emit_event(TradeProposed {
+                id,
+                proposer,
+                counterparty,
+                offered: trade.offered.clone(),
+                requested: trade.requested.clone(),
+                expiry,
+            });
+
+            self.trades.insert(id, trade);
+
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn accept_trade(&mut self, id: TradeId) -> Result<()> {
+            let trade = self.trade(id).ok_or(Error::TradeNotFound)?;
+
+            if self.env().caller() != trade.counterparty {
+                return Err(Error::NotAllowed);
+            }
+
+            if self.env().block_number() > trade.expiry {
+                return Err(Error::TradeExpired);
+            }
+
+            // every token has to still be where it was when the trade was proposed
+            self.assert_trade_ownership(&trade)?;
+
+            self.trades.take(&id);
+
+            for seed in trade.offered.iter() {
+                self.impl_transfer_from(&trade.proposer, &trade.counterparty, *seed)?;
+            }
+
+            for seed in trade.requested.iter() {
+                self.impl_transfer_from(&trade.counterparty, &trade.proposer, *seed)?;
+            }
+
+            self.env().emit_event(TradeAccepted { id });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_trade(&mut self, id: TradeId) -> Result<()> {
+            let trade = self.trade(id).ok_or(Error::TradeNotFound)?;
+            let caller = self.env().caller();
+
+            if caller != trade.proposer && caller != trade.counterparty {
+                return Err(Error::NotAllowed);
+            }
+
+            self.trades.take(&id);
+
+            self.env().emit_event(TradeCancelled { id });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn trade(&self, id: TradeId) -> Option<Trade> {
+            self.trades.get(&id).cloned()
+        }
+
+        #[ink(message)]
+        pub fn royalty_info(&self, seed: Seed, sale_price: Balance) -> (AccountId, Balance) {
+            let (receiver, royalty) = self
+                .species_royalties
+                .get(&self.pokemon_of(seed))
+                .cloned()
+                .unwrap_or((self.royalty_receiver, self.royalty));
+            let royalty = Balance::from(royalty.min(self.royalty_cap));
+            let max = Balance::from(MAX_BASIS_POINTS);
+
+            // split the multiplication so large prices can't overflow. rounds down
+            let amount = sale_price / max * royalty + sale_price % max * royalty / max;
+
+            (receiver, amount)
+        }
+
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        #[ink(message)]
+        pub fn set_admin(&mut self, admin: AccountId) -> Result<()> {
+            self.assert_admin()?;
+            self.assert_valid_account(&admin)?;
+
+            self.admin = admin;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_royalty_receiver(&mut self, receiver: AccountId) -> Result<()> {
+            self.assert_admin()?;
+            self.assert_valid_account(&receiver)?;
+
+            self.royalty_receiver = receiver;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_royalty(&mut self, royalty: BasisPoints) -> Result<()> {
+            self.assert_admin()?;
+
+            if royalty > self.royalty_cap {
+                return Err(Error::RoyaltyTooHigh);
+            }
+
+            self.royalty = royalty;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_species_royalty(
+            &mut self,
+            pokemon: PokemonId,
+            receiver: AccountId,
+            royalty: BasisPoints,
+        ) -> Result<()> {
+            self.assert_admin()?;
+            self.assert_valid_account(&receiver)?;
+
+            if royalty > self.royalty_cap {
+                return Err(Error::RoyaltyTooHigh);
+            }
+
+            self.species_royalties.insert(pokemon, (receiver, royalty));
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn clear_species_royalty(&mut self, pokemon: PokemonId) -> Result<()> {
+            self.assert_admin()?;
+
+            self.species_royalties.take(&pokemon);
+
+            Ok(())
+        }
+
+        // royalties already configured above a lowered cap are capped when paid out
+        #[ink(message)]
+        pub fn set_royalty_cap(&mut self, cap: BasisPoints) -> Result<()> {
+            self.assert_admin()?;
+
+            if cap > MAX_BASIS_POINTS {
+                return Err(Error::RoyaltyTooHigh);
+            }
+
+            self.royalty_cap = cap;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn mint(&mut self, seed: Seed) -> Result<()> {
+            let owner = self.env().caller();
+
+            if self.mint_phase != MintPhase::Public {
+                return Err(Error::MintingClosed);
+            }
+
+            if self.mints_remaining(owner) == Some(0) {
+                return Err(Error::RateLimited);
+            }
+
+            let pokemon = self.roll_pokemon(&owner, seed)?;
+            self.impl_mint(&owner, seed, pokemon)?;
+            self.record_mints(&owner, 1);
+            self.record_pity(&owner, pokemon);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn mint_batch(&mut self, seeds: Vec<Seed>) -> BatchResult<()> {
+            let owner = self.env().caller();
+            let remaining = self.mints_remaining(owner);
+
+            if self.mint_phase != MintPhase::Public {
+                return Err(BatchError::batch(Error::MintingClosed));
+            }
+
+            self.assert_batch(&seeds, |idx, seed| {
+                self.assert_mintable(seed)?;
+
+                if seeds[..idx].contains(&seed) {
+                    return Err(Error::TokenAlreadyExists);
+                }
+
+                if remaining.map_or(false, |remaining| idx as u32 >= remaining) {
+                    return Err(Error::RateLimited);
+                }
+
+                rng::sample(seed)
+                    .map(|_| ())
+                    .map_err(|_| Error::InvalidSeed)
+            })?;
+
+            let amount = seeds.len() as u32;
+
+            for (idx, seed) in seeds.into_iter().enumerate() {
+                let pokemon = self
+                    .roll_pokemon(&owner, seed)
+                    .and_then(|pokemon| self.impl_mint(&owner, seed, pokemon).map(|_| pokemon))
+                    .map_err(|error| BatchError::at(idx, error))?;
+
+                self.record_pity(&owner, pokemon);
+            }
+
+            self.record_mints(&owner, amount);
+
+            Ok(())
+        }
+
+        // `quota` is part of the allowlist leaf, so it has to match what was committed to in the root
+        #[ink(message)]
+        pub fn presale_mint(
+            &mut self,
+            seed: Seed,
+            quota: u32,
+            proof: Vec<merkle::Node>,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+
+            if self.mint_phase != MintPhase::Presale {
+                return Err(Error::MintingClosed);
+            }
+
+            let root = self.presale_root.ok_or(Error::InvalidProof)?;
+
+            if !merkle::verify(root, merkle::leaf(&owner, quota), &proof) {
+                return Err(Error::InvalidProof);
+            }
+
+            let minted = self.presale_minted(owner);
+
+            if minted >= quota {
+                return Err(Error::QuotaExceeded);
+            }
+
+            if self.mints_remaining(owner) == Some(0) {
+                return Err(Error::RateLimited);
+            }
+
+            let pokemon = self.roll_pokemon(&owner, seed)?;
+            self.impl_mint(&owner, seed, pokemon)?;
+            self.presale_mints.insert(owner, minted + 1);
+            self.record_mints(&owner, 1);
+            self.record_pity(&owner, pokemon);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn presale_minted(&self, account: AccountId) -> u32 {
+            self.presale_mints.get(&account).cloned().unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn mint_phase(&self) -> MintPhase {
+            self.mint_phase
+        }
+
+        #[ink(message)]
+        pub fn set_mint_phase(&mut self, phase: MintPhase) -> Result<()> {
+            self.assert_admin()?;
+
+            self.mint_phase = phase;
+
+            self.env().emit_event(MintPhaseChanged { phase });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_presale_root(&mut self, root: Option<merkle::Node>) -> Result<()> {
+            self.assert_admin()?;
+
+            self.presale_root = root;
+
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn open_pack(&mut self, pack_type: PackType) -> Result<Vec<Seed>> {
+            let owner = self.env().caller();
+            let price = self.pack_price(pack_type).ok_or(Error::PackUnavailable)?;
+
+            if self.mint_phase != MintPhase::Public {
+                return Err(Error::MintingClosed);
+            }
+
+            if self.env().transferred_balance() != price {
+                return Err(Error::InvalidPrice);
+            }
+
+            if self
+                .mints_remaining(owner)
+                .map_or(false, |remaining| remaining < pack_type.size())
+            {
+                return Err(Error::RateLimited);
+            }
+
+            let nonce = self.pack_nonces.get(&owner).cloned().unwrap_or(0);
+            let entropy = self.env().random(&nonce.to_le_bytes());
+            let boost = pack_type.rare_boost();
+
+            let mut seeds = Vec::new();
+            let mut pokemon = Vec::new();
+
+            for idx in 0..pack_type.size() {
+                let seed = self
+                    .env()
+                    .hash_encoded::<Blake2x256, _>(&(owner, nonce, entropy, idx));
+                // a pull that's due for pity is rolled from rare pokemon only, like any other mint
+                let id = if self.pity_due(&owner) {
+                    self.roll_pokemon(&owner, seed)?
+                } else {
+                    rng::sample_weighted(seed, |population| {
+                        if population < rng::RARE_POPULATION {
+                            population * boost
+                        } else {
+                            population
+                        }
+                    })?
+                };
+
+                self.impl_mint(&owner, seed, id)?;
+                self.record_pity(&owner, id);
+
+                seeds.push(seed);
+                pokemon.push(id);
+            }
+
+            // pack sales go straight to the admin
+            let admin = self.admin;
+            self.pack_nonces.insert(owner, nonce + 1);
+            self.record_mints(&owner, pack_type.size());
+            self.pay(&admin, price)?;
+
+            self.env().emit_event(PackOpened {
+                owner,
+                pack_type,
+                seeds: seeds.clone(),
+                pokemon,
+            });
+
+            Ok(seeds)
+        }
+
+        #[ink(message)]
+        pub fn pack_price(&self, pack_type: PackType) -> Option<Balance> {
+            self.pack_prices.get(&pack_type).cloned()
+        }
+
+        // packs without a price can't be opened
+        #[ink(message)]
+        pub fn set_pack_price(
+            &mut self,
+            pack_type: PackType,
+            price: Option<Balance>,
+        ) -> Result<()> {
+            self.assert_admin()?;
+
+            match price {
+                Some(price) => self.pack_prices.insert(pack_type, price),
+                None => self.pack_prices.take(&pack_type),
+            };
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn pokedex_of(&self, account: AccountId) -> Vec<PokemonId> {
+            self.pokedexes
+                .get(&account)
+                .map(pokedex::species)
+                .unwrap_or_default()
+        }
+
+        // number of species registered, out of 151
+        #[ink(message)]
+        pub fn pokedex_completion(&self, account: AccountId) -> u32 {
+            self.pokedexes.get(&account).map_or(0, pokedex::count)
+        }
+
+        // badges are soulbound: they live outside of `seeds`, so no transfer path can move them
+        #[ink(message)]
+        pub fn register_badge(&mut self, badge: BadgeId, name: String) -> Result<()> {
+            self.assert_admin()?;
+
+            if self.badges.contains_key(&badge) {
+                return Err(Error::BadgeAlreadyExists);
+            }
+
+            self.badges.insert(badge, name);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn award_badge(&mut self, account: AccountId, badge: BadgeId) -> Result<()> {
+            self.assert_admin()?;
+            self.assert_valid_account(&account)?;
+
+            self.impl_award_badge(&account, badge)
+        }
+
+        #[ink(message)]
+        pub fn badges_of(&self, account: AccountId) -> Vec<BadgeId> {
+            self.account_badges
+                .get(&account)
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn badge_name(&self, badge: BadgeId) -> Option<String> {
+            self.badges.get(&badge).cloned()
+        }
+
+        // badge awarded automatically when an account reaches the milestone
+        #[ink(message)]
+        pub fn set_milestone_badge(
+            &mut self,
+            milestone: Milestone,
+            badge: Option<BadgeId>,
+        ) -> Result<()> {
+            self.assert_admin()?;
+
+            match badge {
+                Some(badge) if !self.badges.contains_key(&badge) => {
+                    return Err(Error::BadgeNotFound)
+                }
+                Some(badge) => self.milestone_badges.insert(milestone, badge),
+                None => self.milestone_badges.take(&milestone),
+            };
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn pity_of(&self, account: AccountId) -> u32 {
+            self.pity.get(&account).cloned().unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn pity_threshold(&self) -> u32 {
+            self.pity_threshold
+        }
+
+        // a threshold of 0 disables the pity system
+        #[ink(message)]
+        pub fn set_pity_threshold(&mut self, threshold: u32) -> Result<()> {
+            self.assert_admin()?;
+
+            self.pity_threshold = threshold;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn mints_remaining(&self, account: AccountId) -> Option<u32> {
+            let now = self.env().block_number();
+            let MintLimits {
+                per_account,
+                per_window,
+                ..
+            } = self.mint_limits;
+
+            let account_remaining = per_account.map(|max| {
+                max.saturating_sub(self.account_mints.get(&account).cloned().unwrap_or(0))
+            });
+
+            let window_remaining =
+                per_window.map(|(max, window)| match self.window_mints.get(&account) {
+                    Some((start, count)) if now < start.saturating_add(window) => {
+                        max.saturating_sub(*count)
+                    }
+                    _ => max,
+                });
+
+            [
+                account_remaining,
+                window_remaining,
+                self.block_mints_remaining(),
+            ]
+            .iter()
+            .flatten()
+            .min()
+            .cloned()
+        }
+
+        #[ink(message)]
+        pub fn mint_limits(&self) -> MintLimits {
+            self.mint_limits
+        }
+
+        #[ink(message)]
+        pub fn set_mint_limits(&mut self, limits: MintLimits) -> Result<()> {
+            self.assert_admin()?;
+
+            self.mint_limits = limits;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_batch(&mut self, to: AccountId, seeds: Vec<Seed>) -> BatchResult<()> {
+            let caller = self.env().caller();
+
+            self.assert_batch(&seeds, |idx, seed| {
+                self.assert_exists(seed)?;
+                self.assert_owner_or_approved(seed)?;
+                self.assert_valid_account(&to)?;
+
+                if self.owner_of(seed) != caller {
+                    return Err(Error::NotOwner);
+                }
+
+                if seeds[..idx].contains(&seed) {
+                    return Err(Error::DuplicateSeed);
+                }
+
+                Ok(())
+            })?;
+
+            for (idx, seed) in seeds.into_iter().enumerate() {
+                self.impl_transfer_from(&caller, &to, seed)
+                    .map_err(|error| BatchError::at(idx, error))?;
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn approve_batch(&mut self, approved: AccountId, seeds: Vec<Seed>) -> BatchResult<()> {
+            self.assert_batch(&seeds, |_, seed| {
+                self.assert_exists(seed)?;
+                self.assert_valid_account(&approved)?;
+                self.assert_owner_or_approved(seed)?;
+                self.assert_unlocked(seed)
+            })?;
+
+            for seed in seeds.into_iter() {
+                self.impl_approve(&approved, seed);
+            }
+
+            Ok(())
+        }
+
+        // minters can mint while the mint phase is closed, for giveaways and rewards. the mint
+        // limits still apply, counted against the recipient
+        #[ink(message)]
+        pub fn mint_to(&mut self, recipient: AccountId, seed: Seed) -> Result<()> {
+            self.assert_minter()?;
+            self.assert_valid_account(&recipient)?;
+
+            if self.mints_remaining(recipient) == Some(0) {
+                return Err(Error::RateLimited);
+            }
+
+            let pokemon = rng::sample(seed).map_err(|_| Error::InvalidSeed)?;
+
+            self.impl_mint(&recipient, seed, pokemon)?;
+            self.record_mints(&recipient, 1);
+
+            Ok(())
+        }
+
+        // like `mint_to`, ignores the mint phase but not the mint limits
+        #[ink(message)]
+        pub fn airdrop(&mut self, drops: Vec<(AccountId, Seed)>) -> BatchResult<()> {
+            self.assert_minter().map_err(BatchError::batch)?;
+            let block_remaining = self.block_mints_remaining();
+
+            self.assert_batch(&drops, |idx, (recipient, seed)| {
+                self.assert_valid_account(&recipient)?;
+
+                self.assert_mintable(seed)?;
+
+                if drops[..idx].iter().any(|(_, s)| *s == seed) {
+                    return Err(Error::TokenAlreadyExists);
+                }
+
+                let earlier = drops[..idx].iter().filter(|(r, _)| *r == recipient).count() as u32;
+
+                if block_remaining.map_or(false, |remaining| idx as u32 >= remaining)
+                    || self
+                        .mints_remaining(recipient)
+                        .map_or(false, |remaining| earlier >= remaining)
+                {
+                    return Err(Error::RateLimited);
+                }
+
+                rng::sample(seed)
+                    .map(|_| ())
+                    .map_err(|_| Error::InvalidSeed)
+            })?;
+
+            for (idx, (recipient, seed)) in drops.into_iter().enumerate() {
+                rng::sample(seed)
+                    .map_err(|_| Error::InvalidSeed)
+                    .and_then(|pokemon| self.impl_mint(&recipient, seed, pokemon))
+                    .map_err(|error| BatchError::at(idx, error))?;
+
+                self.record_mints(&recipient, 1);
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn redeem_voucher(&mut self, voucher: Voucher, signature: Vec<u8>) -> Result<()> {
+            let signer = self.voucher_signer.ok_or(Error::InvalidSignature)?;
+
+            if self.env().block_number() > voucher.expires_at {
+                return Err(Error::VoucherExpired);
+            }
+
+            if self.redeemed_vouchers.contains_key(&voucher.nonce) {
+                return Err(Error::VoucherAlreadyRedeemed);
+            }
+
+            if signature.len() != 65 {
+                return Err(Error::InvalidSignature);
+            }
+
+            let mut sig = [0u8; 65];
+            sig.copy_from_slice(&signature);
+            let message = self.env().hash_encoded::<Blake2x256, _>(&(
+                VOUCHER_DOMAIN,
+                self.env().account_id(),
+                voucher,
+            ));
+            let public_key = self
+                .env()
+                .ecdsa_recover(&sig, &message)
+                .map_err(|_| Error::InvalidSignature)?;
+
+            if AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key)) != signer {
+                return Err(Error::InvalidSignature);
+            }
+
+            let pokemon = match voucher.pokemon {
+                Some(pokemon) if pokemon >= 1 && pokemon as usize <= rng::POKEMON_LIST.len() => {
+                    pokemon
+                }
+                Some(_) => return Err(Error::InvalidSeed),
+                None => rng::sample(voucher.seed).map_err(|_| Error::InvalidSeed)?,
+            };
+
+            self.assert_valid_account(&voucher.recipient)?;
+            self.impl_mint(&voucher.recipient, voucher.seed, pokemon)?;
+            self.redeemed_vouchers.insert(voucher.nonce, true);
+
+            self.env().emit_event(VoucherRedeemed {
+                nonce: voucher.nonce,
+                recipient: voucher.recipient,
+                seed: voucher.seed,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn voucher_signer(&self) -> Option<AccountId> {
+            self.voucher_signer
+        }
+
+        // the signer is identified the same way substrate derives ECDSA accounts: the blake2
+        // hash of its compressed public key
+        #[ink(message)]
+        pub fn set_voucher_signer(&mut self, signer: Option<AccountId>) -> Result<()> {
+            self.assert_admin()?;
+
+            self.voucher_signer = signer;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_minter(&self, account: AccountId) -> bool {
+            *self.minters.get(&account).unwrap_or(&false)
+        }
+
+        #[ink(message)]
+        pub fn set_minter(&mut self, account: AccountId, minter: bool) -> Result<()> {
+            self.assert_admin()?;
+            self.assert_valid_account(&account)?;
+
+            self.minters
+                .entry(account)
+                .and_modify(|v| *v = minter)
+                .or_insert(minter);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn max_batch_size(&self) -> u32 {
+            self.max_batch_size
+        }
+
+        #[ink(message)]
+        pub fn set_max_batch_size(&mut self, max_batch_size: u32) -> Result<()> {
+            self.assert_admin()?;
+
+            self.max_batch_size = max_batch_size;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn breed(&mut self, parent_a: Seed, parent_b: Seed) -> Result<Seed> {
+            self.assert_exists(parent_a)?;
+            self.assert_exists(parent_b)?;
+            self.assert_owner_or_approved(parent_a)?;
+            self.assert_owner_or_approved(parent_b)?;
+
+            let pokemon_a = self.pokemon_of(parent_a);
+            let pokemon_b = self.pokemon_of(parent_b);
+
+            if parent_a == parent_b || !breeding::is_compatible(pokemon_a, pokemon_b) {
+                return Err(Error::IncompatiblePair);
+            }
+
+            let now = self.env().block_number();
+
+            if self.breeding_cooldown_of(parent_a) > now
+                || self.breeding_cooldown_of(parent_b) > now
+            {
+                return Err(Error::BreedingCooldown);
+            }
+
+            let entropy = self
+                .env()
+                .hash_encoded::<Blake2x256, _>(&(parent_a, parent_b, now));
+            let egg = breeding::child_seed(parent_a, parent_b, entropy);
+
+            self.assert_mintable(egg)?;
+
+            let hatches_at = now + HATCH_DELAY;
+
+            self.eggs.insert(
+                egg,
+                Egg {
+                    owner: self.owner_of(parent_a),
+                    pokemon: breeding::offspring(pokemon_a, pokemon_b, entropy),
+                    hatches_at,
+                },
+            );
+            self.breeding_cooldowns
+                .insert(parent_a, now + BREEDING_COOLDOWN);
+            self.breeding_cooldowns
+                .insert(parent_b, now + BREEDING_COOLDOWN);
+
+            self.env().emit_event(Bred {
+                parent_a,
+                parent_b,
+                egg,
+                hatches_at,
+            });
+
+            Ok(egg)
+        }
+
+        #[ink(message)]
+        pub fn hatch(&mut self, egg: Seed) -> Result<()> {
+            let Egg {
+                owner,
+                pokemon,
+                hatches_at,
+            } = *self.eggs.get(&egg).ok_or(Error::EggNotFound)?;
+
+            if owner != self.env().caller() {
+                return Err(Error::NotOwner);
+            }
+
+            if self.env().block_number() < hatches_at {
+                return Err(Error::EggNotReady);
+            }
+
+            self.add_token_to(&owner, egg, pokemon)?;
+            self.eggs.take(&egg);
+
+            self.env().emit_event(Hatched { seed: egg, pokemon });
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(owner),
+                seed: egg,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn egg_of(&self, seed: Seed) -> Option<Egg> {
+            self.eggs.get(&seed).cloned()
+        }
+
+        #[ink(message)]
+        pub fn eggs_of(&self, owner: AccountId) -> Vec<Seed> {
+            self.eggs
+                .iter()
+                .filter(|(_, egg)| egg.owner == owner)
+                .map(|(seed, _)| *seed)
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn breeding_cooldown_of(&self, seed: Seed) -> BlockNumber {
+            self.breeding_cooldowns.get(&seed).cloned().unwrap_or(0)
+        }
+
+        // the caller's pokemon attacks any other pokemon. the outcome only depends on both seeds
+        // and the block's randomness, so anyone can replay it off-chain. casual battles are free,
+        // so they're fought at `CASUAL_LEVEL` and don't award experience
+        #[ink(message)]
+        pub fn battle(&mut self, attacker: Seed, defender: Seed) -> Result<BattleOutcome> {
+            self.assert_exists(attacker)?;
+            self.assert_exists(defender)?;
+
+            if self.user_of(attacker) != Some(self.env().caller()) {
+                self.assert_owner_or_approved(attacker)?;
+            }
+
+            let entropy = self.env().random(&attacker);
+            self.resolve_battle(attacker, defender, entropy, false)
+        }
+
+        // challenges `opponent`'s pokemon, staking the transferred value. the challenger's pokemon
+        // is locked until the challenge is settled, cancelled or expires. `commitment` is the
+        // blake2x256 hash of a secret the challenger reveals after the opponent accepts, so neither
+        // side can know the outcome before both are committed
+        #[ink(message, payable)]
+        pub fn challenge(
+            &mut self,
+            my_seed: Seed,
+            opponent: AccountId,
+            their_seed: Seed,
+            stake: Balance,
+            commitment: [u8; 32],
+        ) -> Result<ChallengeId> {
+            let challenger = self.env().caller();
+            self.assert_exists(my_seed)?;
+            self.assert_exists(their_seed)?;
+
+            if !self.is_player(&challenger, my_seed) || !self.is_player(&opponent, their_seed) {
+                return Err(Error::NotOwner);
+            }
+
+            if opponent == challenger || my_seed == their_seed {
+                return Err(Error::InvalidOpponent);
+            }
+
+            self.assert_unlocked(my_seed)?;
+
+            if self.env().transferred_balance() != stake {
+                return Err(Error::InvalidPrice);
+            }
+
+            let id = self.next_challenge_id;
+            let expiry = self.env().block_number() + CHALLENGE_TIMEOUT;
+            self.next_challenge_id += 1;
+
+            self.challenges.insert(
+                id,
+                Challenge {
+                    challenger,
+                    challenger_seed: my_seed,
+                    opponent,
+                    opponent_seed: their_seed,
+                    stake,
+                    expiry,
+                    commitment,
+                    opponent_entropy: None,
+                    reveal_by: 0,
+                },
+            );
+            // the lock runs out on its own once the challenge can't be accepted anymore
+            let contract = self.env().account_id();
+            self.impl_lock(my_seed, LockReason::Challenge, contract, expiry + 1);
+
+            self.env().emit_event(ChallengeCreated {
+                id,
+                challenger,
+                opponent,
+                stake,
+                expiry,
+            });
+
+            Ok(id)
+        }
+
+        // matches the stake and locks the opponent's pokemon. the battle is fought once the
+        // challenger reveals, mixing their secret with `entropy`
+        #[ink(message, payable)]
+        pub fn accept_challenge(&mut self, id: ChallengeId, entropy: [u8; 32]) -> Result<()> {
+            let mut challenge = self.challenge_of(id).ok_or(Error::ChallengeNotFound)?;
+
+            if self.env().caller() != challenge.opponent {
+                return Err(Error::NotAllowed);
+            }
+
+            if challenge.opponent_entropy.is_some() {
+                return Err(Error::ChallengeAccepted);
+            }
+
+            if self.env().block_number() > challenge.expiry {
+                return Err(Error::ChallengeExpired);
+            }
+
+            if !self.is_player(&challenge.opponent, challenge.opponent_seed) {
+                return Err(Error::NotOwner);
+            }
+
+            self.assert_unlocked(challenge.opponent_seed)?;
+
+            if self.env().transferred_balance() != challenge.stake {
+                return Err(Error::InvalidPrice);
+            }
+
+            let reveal_by = self.env().block_number() + CHALLENGE_REVEAL_TIMEOUT;
+            challenge.opponent_entropy = Some(entropy);
+            challenge.reveal_by = reveal_by;
+            self.challenges.insert(id, challenge);
+
+            // both pokemon stay locked until the challenge is settled or forfeited
+            let contract = self.env().account_id();
+            for seed in [challenge.challenger_seed, challenge.opponent_seed].iter() {
+                self.impl_lock(*seed, LockReason::Challenge, contract, reveal_by + 1);
+            }
+
+            self.env().emit_event(ChallengeAccepted { id, reveal_by });
+
+            Ok(())
+        }
+
+        // the challenger reveals the secret behind their commitment and the battle is fought.
+        // the winner takes both stakes
+        #[ink(message)]
+        pub fn reveal_challenge(
+            &mut self,
+            id: ChallengeId,
+            secret: [u8; 32],
+        ) -> Result<BattleOutcome> {
+            let challenge = self.challenge_of(id).ok_or(Error::ChallengeNotFound)?;
+            let entropy = challenge
+                .opponent_entropy
+                .ok_or(Error::ChallengeNotAccepted)?;
+
+            if self.env().caller() != challenge.challenger {
+                return Err(Error::NotAllowed);
+            }
+
+            if self.env().block_number() > challenge.reveal_by {
+                return Err(Error::ChallengeExpired);
+            }
+
+            if self.env().hash_bytes::<Blake2x256>(&secret) != challenge.commitment {
+                return Err(Error::InvalidReveal);
+            }
+
+            let outcome = self.resolve_battle(
+                challenge.challenger_seed,
+                challenge.opponent_seed,
+                (secret, entropy),
+                challenge.stake > 0,
+            )?;
+            let challenger = (challenge.challenger, challenge.challenger_seed);
+            let opponent = (challenge.opponent, challenge.opponent_seed);
+            let (winner, loser) = if outcome.winner == challenge.challenger_seed {
+                (challenger, opponent)
+            } else {
+                (opponent, challenger)
+            };
+
+            self.settle_challenge(id, &challenge, winner, loser)?;
+
+            Ok(outcome)
+        }
+
+        // a challenger who doesn't reveal in time loses. anyone can settle it for the opponent
+        #[ink(message)]
+        pub fn claim_forfeit(&mut self, id: ChallengeId) -> Result<()> {
+            let challenge = self.challenge_of(id).ok_or(Error::ChallengeNotFound)?;
+
+            if challenge.opponent_entropy.is_none() {
+                return Err(Error::ChallengeNotAccepted);
+            }
+
+            if self.env().block_number() <= challenge.reveal_by {
+                return Err(Error::NotAllowed);
+            }
+
+            self.settle_challenge(
+                id,
+                &challenge,
+                (challenge.opponent, challenge.opponent_seed),
+                (challenge.challenger, challenge.challenger_seed),
+            )
+        }
+
+        // the challenger can cancel at any time before it's accepted. once expired, anyone can,
+        // to release the stake
+        #[ink(message)]
+        pub fn cancel_challenge(&mut self, id: ChallengeId) -> Result<()> {
+            let challenge = self.challenge_of(id).ok_or(Error::ChallengeNotFound)?;
+
+            if challenge.opponent_entropy.is_some() {
+                return Err(Error::ChallengeAccepted);
+            }
+
+            if self.env().caller() != challenge.challenger
+                && self.env().block_number() <= challenge.expiry
+            {
+                return Err(Error::NotAllowed);
+            }
+
+            let contract = self.env().account_id();
+            self.challenges.take(&id);
+            self.impl_unlock(challenge.challenger_seed, LockReason::Challenge, contract);
+            self.pay(&challenge.challenger, challenge.stake)?;
+
+            self.env().emit_event(ChallengeCancelled { id });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn challenge_of(&self, id: ChallengeId) -> Option<Challenge> {
+            self.challenges.get(&id).cloned()
+        }
+
+        #[ink(message)]
+        pub fn is_locked(&self, seed: Seed) -> bool {
+            !self.locks_of(seed).is_empty()
+        }
+
+        // locks that are still active. expired ones no longer count
+        #[ink(message)]
+        pub fn locks_of(&self, seed: Seed) -> Vec<Lock> {
+            let now = self.env().block_number();
+
+            self.locks
+                .get(&seed)
+                .map(|locks| {
+                    locks
+                        .iter()
+                        .filter(|lock| now < lock.until)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or(Vec::new())
+        }
+
+        // the owner or a registered game contract can lock the token until the given block, at
+        // most `MAX_LOCK_DURATION` ahead. locking again with the same reason moves the deadline.
+        // tokens held in escrow by this contract can't be locked, or nobody could release them
+        #[ink(message)]
+        pub fn lock(
+            &mut self,
+            seed: Seed,
+            reason: LockReason,
+            until_block: BlockNumber,
+        ) -> Result<()> {
+            let locker = self.env().caller();
+            self.assert_exists(seed)?;
+
+            let owner = self.owner_of(seed);
+
+            if owner == self.env().account_id() || (owner != locker && !self.is_game(locker)) {
+                return Err(Error::NotAllowed);
+            }
+
+            let now = self.env().block_number();
+
+            if until_block <= now || until_block - now > MAX_LOCK_DURATION {
+                return Err(Error::InvalidDeadline);
+            }
+
+            self.impl_lock(seed, reason, locker, until_block);
+
+            Ok(())
+        }
+
+        // only whoever placed a lock can lift it early
+        #[ink(message)]
+        pub fn unlock(&mut self, seed: Seed, reason: LockReason) -> Result<()> {
+            let locker = self.env().caller();
+
+            if !self.impl_unlock(seed, reason, locker) {
+                return Err(Error::LockNotFound);
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn burn(&mut self, seed: Seed) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
+            self.assert_unlocked(seed)?;
+
+            let owner = self.owner_of(seed);
+
+            self.impl_clear_approval(seed)?;
+            self.clear_listing(seed);
+            self.breeding_cooldowns.take(&seed);
+            self.experience.take(&seed);
+            self.locks.take(&seed);
+            self.attributes.take(&seed);
+            self.clear_user(seed);
+            self.clear_loan_offer(seed);
+            self.remove_token_from(&owner, seed)?;
+            self.burned.insert(seed, true);
+
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: None,
+                seed,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn create_tournament(
+            &mut self,
+            entry_fee: Balance,
+            max_entrants: u32,
+        ) -> Result<TournamentId> {
+            self.assert_admin()?;
+
+            let id = self.next_tournament_id;
+            self.next_tournament_id += 1;
+
+            self.tournaments.insert(
+                id,
+                Tournament {
+                    entry_fee,
+                    max_entrants,
+                    prize_pool: 0,
+                    round: 0,
+                    entrants: Vec::new(),
+                    registrants: Vec::new(),
+                    winner: None,
+                    finished: false,
+                },
+            );
+
+            self.env().emit_event(TournamentCreated {
+                id,
+                entry_fee,
+                max_entrants,
+            });
+
+            Ok(id)
+        }
+
+        // enters a pokemon, paying the entry fee. it stays locked until it's eliminated or wins
+        #[ink(message, payable)]
+        pub fn register(&mut self, tournament: TournamentId, seed: Seed) -> Result<()> {
+            let owner = self.env().caller();
+            let mut entry = self
+                .tournament(tournament)
+                .ok_or(Error::TournamentNotFound)?;
+            self.assert_exists(seed)?;
+
+            if !self.is_player(&owner, seed) {
+                return Err(Error::NotOwner);
+            }
+
+            self.assert_unlocked(seed)?;
+
+            if entry.round > 0 || entry.finished {
+                return Err(Error::TournamentStarted);
+            }
+
+            if entry.entrants.len() as u32 >= entry.max_entrants {
+                return Err(Error::TournamentFull);
+            }
+
+            if self.env().transferred_balance() != entry.entry_fee {
+                return Err(Error::InvalidPrice);
+            }
+
+            entry.entrants.push(seed);
+            entry.registrants.push((seed, owner));
+            entry.prize_pool += entry.entry_fee;
+            let contract = self.env().account_id();
+            self.tournaments.insert(tournament, entry);
+            self.impl_lock(seed, LockReason::Tournament, contract, BlockNumber::MAX);
+
+            self.env().emit_event(TournamentEntered {
+                id: tournament,
+                owner,
+                seed,
+            });
+
+            Ok(())
+        }
+
+        // closes registration on the first call. entrants are paired up in registration order,
+        // with the last one getting a bye on odd rounds. the last one standing takes the prize pool
+        #[ink(message)]
+        pub fn advance_round(&mut self, tournament: TournamentId) -> Result<()> {
+            self.assert_admin()?;
+            let mut entry = self
+                .tournament(tournament)
+                .ok_or(Error::TournamentNotFound)?;
+
+            if entry.finished {
+                return Err(Error::TournamentFinished);
+            }
+
+            let contract = self.env().account_id();
+
+            if entry.entrants.len() > 1 {
+                let mut remaining = Vec::new();
+
+                for pair in entry.entrants.chunks(2) {
+                    match pair {
+                        [a, b] => {
+                            let entropy = self.env().random(a);
+                            let outcome = self.resolve_battle(*a, *b, entropy, true)?;
+                            let loser = if outcome.winner == *a { b } else { a };
+
+                            self.impl_unlock(*loser, LockReason::Tournament, contract);
+                            self.record_match(
+                                (self.registrant_of(&entry, outcome.winner), outcome.winner),
+                                (self.registrant_of(&entry, *loser), *loser),
+                            );
+                            remaining.push(outcome.winner);
+                        }
+                        _ => remaining.push(pair[0]),
+                    }
+                }
+
+                entry.round += 1;
+                entry.entrants = remaining;
+
+                self.env().emit_event(RoundAdvanced {
+                    id: tournament,
+                    round: entry.round,
+                    remaining: entry.entrants.clone(),
+                });
+            }
+
+            if entry.entrants.len() <= 1 {
+                let winner = entry.entrants.first().cloned();
+                let prize = entry.prize_pool;
+
+                entry.winner = winner;
+                entry.finished = true;
+                entry.prize_pool = 0;
+
+                if let Some(winner) = winner {
+                    let registrant = self.registrant_of(&entry, winner);
+                    self.impl_unlock(winner, LockReason::Tournament, contract);
+                    self.pay(&registrant, prize)?;
+                }
+
+                self.env().emit_event(TournamentWon {
+                    id: tournament,
+                    winner,
+                    prize,
+                });
+            }
+
+            self.tournaments.insert(tournament, entry);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn tournament(&self, id: TournamentId) -> Option<Tournament> {
+            self.tournaments.get(&id).cloned()
+        }
+
+        #[ink(message)]
+        pub fn experience_of(&self, seed: Seed) -> u32 {
+            self.experience.get(&seed).cloned().unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn level_of(&self, seed: Seed) -> u8 {
+            battle::level(self.experience_of(seed))
+        }
+
+        #[ink(message)]
+        pub fn stats_of(&self, seed: Seed) -> Option<Stats> {
+            if !self.exists(seed) {
+                return None;
+            }
+
+            battle::stats(self.pokemon_of(seed), rng::ivs(seed), self.level_of(seed)).ok()
+        }
+
+        // the token is escrowed by the contract, gaining experience until it's withdrawn
+        #[ink(message)]
+        pub fn deposit_daycare(&mut self, seed: Seed) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
+
+            let owner = self.owner_of(seed);
+            let contract = self.env().account_id();
+            self.impl_transfer_from(&owner, &contract, seed)?;
+
+            self.daycare.insert(
+                seed,
+                DaycareStay {
+                    owner,
+                    since: self.env().block_number(),
+                },
+            );
+
+            self.env().emit_event(DaycareDeposited { seed, owner });
+
+            Ok(())
+        }
+
+        // returns the token with the experience it gained. the fee is due for every level gained
+        #[ink(message, payable)]
+        pub fn withdraw_daycare(&mut self, seed: Seed) -> Result<()> {
+            let stay = self.daycare_of(seed).ok_or(Error::NotInDaycare)?;
+            let fee = self.daycare_fee_of(seed).ok_or(Error::NotInDaycare)?;
+
+            if self.env().caller() != stay.owner {
+                return Err(Error::NotAllowed);
+            }
+
+            if self.env().transferred_balance() != fee {
+                return Err(Error::InvalidPrice);
+            }
+
+            let experience = self.daycare_experience(&stay);
+            let contract = self.env().account_id();
+            let admin = self.admin;
+
+            self.daycare.take(&seed);
+            self.impl_transfer_from(&contract, &stay.owner, seed)?;
+            self.add_experience(seed, experience);
+            self.pay(&admin, fee)?;
+
+            self.env().emit_event(DaycareWithdrawn {
+                seed,
+                owner: stay.owner,
+                experience,
+                fee,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn daycare_of(&self, seed: Seed) -> Option<DaycareStay> {
+            self.daycare.get(&seed).cloned()
+        }
+
+        // tokens an account has in the daycare. they're owned by the contract in the meantime
+        #[ink(message)]
+        pub fn staked_of(&self, owner: AccountId) -> Vec<Seed> {
+            self.daycare
+                .iter()
+                .filter(|(_, stay)| stay.owner == owner)
+                .map(|(seed, _)| *seed)
+                .collect()
+        }
+
+        // what withdrawing the token right now would cost
+        #[ink(message)]
+        pub fn daycare_fee_of(&self, seed: Seed) -> Option<Balance> {
+            let stay = self.daycare_of(seed)?;
+            let experience = self
+                .experience_of(seed)
+                .saturating_add(self.daycare_experience(&stay));
+            let levels = battle::level(experience) - self.level_of(seed);
+
+            Some(self.daycare_fee * Balance::from(levels))
+        }
+
+        #[ink(message)]
+        pub fn daycare_fee(&self) -> Balance {
+            self.daycare_fee
+        }
+
+        #[ink(message)]
+        pub fn set_daycare_fee(&mut self, fee: Balance) -> Result<()> {
+            self.assert_admin()?;
+
+            self.daycare_fee = fee;
+
+            Ok(())
+        }
+
+        // asks for `principal` against the token, to be paid back with `interest` within
+        // `duration` blocks. the token stays with the borrower until a lender funds the loan
+        #[ink(message)]
+        pub fn offer_loan(
+            &mut self,
+            seed: Seed,
+            principal: Balance,
+            interest: Balance,
+            duration: BlockNumber,
+        ) -> Result<()> {
+            let borrower = self.env().caller();
+            self.assert_exists(seed)?;
+
+            if self.owner_of(seed) != borrower {
+                return Err(Error::NotOwner);
+            }
+
+            self.assert_unlocked(seed)?;
+
+            // terms can't be changed under a lender's feet. cancel the offer first
+            if self.loans.contains_key(&seed) {
+                return Err(Error::LoanExists);
+            }
+
+            if principal == 0 || principal.checked_add(interest).is_none() {
+                return Err(Error::InvalidPrice);
+            }
+
+            if duration == 0 || self.env().block_number().checked_add(duration).is_none() {
+                return Err(Error::InvalidDeadline);
+            }
+
+            self.loans.insert(
+                seed,
+                Loan {
+                    borrower,
+                    lender: None,
+                    principal,
+                    interest,
+                    duration,
+                    due: 0,
+                },
+            );
+
+            self.env().emit_event(LoanOffered {
+                seed,
+                borrower,
+                principal,
+                interest,
+                duration,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_loan_offer(&mut self, seed: Seed) -> Result<()> {
+            let loan = self.loan_of(seed).ok_or(Error::LoanNotFound)?;
+
+            if self.env().caller() != loan.borrower {
+                return Err(Error::NotAllowed);
+            }
+
+            if loan.lender.is_some() {
+                return Err(Error::LoanFunded);
+            }
+
+            self.loans.take(&seed);
+
+            self.env().emit_event(LoanCancelled { seed });
+
+            Ok(())
+        }
+
+        // pays the principal to the borrower and escrows the token until the loan is settled.
+        // the lender passes the interest and duration they agreed to, which must match the offer
+        #[ink(message, payable)]
+        pub fn fund_loan(
+            &mut self,
+            seed: Seed,
+            interest: Balance,
+            duration: BlockNumber,
+        ) -> Result<()> {
+            let mut loan = self.loan_of(seed).ok_or(Error::LoanNotFound)?;
+            let lender = self.env().caller();
+
+            if loan.lender.is_some() {
+                return Err(Error::LoanFunded);
+            }
+
+            if lender == loan.borrower {
+                return Err(Error::NotAllowed);
+            }
+
+            if loan.interest != interest || loan.duration != duration {
+                return Err(Error::LoanTermsChanged);
+            }
+
+            if self.env().transferred_balance() != loan.principal {
+                return Err(Error::InvalidPrice);
+            }
+
+            let due = self
+                .env()
+                .block_number()
+                .checked_add(loan.duration)
+                .ok_or(Error::InvalidDeadline)?;
+
+            // the borrower may have locked the token after offering
+            if self.owner_of(seed) != loan.borrower {
+                return Err(Error::NotOwner);
+            }
+
+            self.assert_unlocked(seed)?;
+
+            let contract = self.env().account_id();
+
+            // the offer is kept, it's about to be funded
+            self.pay(&loan.borrower, loan.principal)?;
+            self.move_token(&loan.borrower, &contract, seed)?;
+
+            loan.lender = Some(lender);
+            loan.due = due;
+            self.loans.insert(seed, loan);
+
+            self.env().emit_event(LoanFunded {
+                seed,
+                lender,
+                due: loan.due,
+            });
+
+            Ok(())
+        }
+
+        // principal plus interest, up to and including the due block
+        #[ink(message, payable)]
+        pub fn repay_loan(&mut self, seed: Seed) -> Result<()> {
+            let loan = self.loan_of(seed).ok_or(Error::LoanNotFound)?;
+            let lender = loan.lender.ok_or(Error::LoanNotFunded)?;
+
+            if self.env().caller() != loan.borrower {
+                return Err(Error::NotAllowed);
+            }
+
+            if self.env().block_number() > loan.due {
+                return Err(Error::LoanDefaulted);
+            }
+
+            let amount = loan.principal + loan.interest;
+
+            if self.env().transferred_balance() != amount {
+                return Err(Error::InvalidPrice);
+            }
+
+            let contract = self.env().account_id();
+
+            self.pay(&lender, amount)?;
+            self.impl_transfer_from(&contract, &loan.borrower, seed)?;
+            self.loans.take(&seed);
+
+            self.env().emit_event(LoanRepaid {
+                seed,
+                borrower: loan.borrower,
+                lender,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        // the lender keeps the token once the loan wasn't repaid in time
+        #[ink(message)]
+        pub fn claim_collateral(&mut self, seed: Seed) -> Result<()> {
+            let loan = self.loan_of(seed).ok_or(Error::LoanNotFound)?;
+            let lender = loan.lender.ok_or(Error::LoanNotFunded)?;
+
+            if self.env().caller() != lender {
+                return Err(Error::NotAllowed);
+            }
+
+            if self.env().block_number() <= loan.due {
+                return Err(Error::LoanNotDefaulted);
+            }
+
+            let contract = self.env().account_id();
+
+            self.impl_transfer_from(&contract, &lender, seed)?;
+            self.loans.take(&seed);
+
+            self.env().emit_event(CollateralClaimed { seed, lender });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn loan_of(&self, seed: Seed) -> Option<Loan> {
+            self.loans.get(&seed).cloned()
+        }
+
+        // tokens on offer as collateral that no lender has funded yet
+        #[ink(message)]
+        pub fn loan_offers(&self) -> Vec<Seed> {
+            self.loans
+                .iter()
+                .filter(|(_, loan)| loan.lender.is_none())
+                .map(|(seed, _)| *seed)
+                .collect()
+        }
+
+        // experience from game actions other than battles, e.g. quests
+        #[ink(message)]
+        pub fn grant_experience(&mut self, seed: Seed, amount: u32) -> Result<()> {
+            if !self.is_game(self.env().caller()) {
+                self.assert_admin()?;
+            }
+
+            self.assert_exists(seed)?;
+
+            self.add_experience(seed, amount);
+
+            Ok(())
+        }
+
+        // lends the token to `user` until `expires`, without giving up ownership. users can
+        // battle with the token and enter it in challenges and tournaments. `None` clears it
+        #[ink(message)]
+        pub fn set_user(
+            &mut self,
+            seed: Seed,
+            user: Option<AccountId>,
+            expires: BlockNumber,
+        ) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_owner_or_approved(seed)?;
+
+            match user {
+                Some(user) => {
+                    self.assert_valid_account(&user)?;
+
+                    if expires < self.env().block_number() {
+                        return Err(Error::InvalidDeadline);
+                    }
+
+                    self.users.insert(seed, (user, expires));
+
+                    self.env().emit_event(UpdateUser {
+                        seed,
+                        user: Some(user),
+                        expires,
+                    });
+                }
+                None => self.clear_user(seed),
+            }
+
+            Ok(())
+        }
+
+        // the user right lasts up to and including the `expires` block
+        #[ink(message)]
+        pub fn user_of(&self, seed: Seed) -> Option<AccountId> {
+            match self.users.get(&seed) {
+                Some((user, expires)) if self.env().block_number() <= *expires => Some(*user),
+                _ => None,
+            }
+        }
+
+        #[ink(message)]
+        pub fn user_expires(&self, seed: Seed) -> BlockNumber {
+            self.users.get(&seed).map_or(0, |(_, expires)| *expires)
+        }
+
+        // game contracts can write token attributes and grant experience
+        #[ink(message)]
+        pub fn is_game(&self, account: AccountId) -> bool {
+            *self.games.get(&account).unwrap_or(&false)
+        }
+
+        #[ink(message)]
+        pub fn set_game(&mut self, account: AccountId, game: bool) -> Result<()> {
+            self.assert_admin()?;
+            self.assert_valid_account(&account)?;
+
+            self.games
+                .entry(account)
+                .and_modify(|v| *v = game)
+                .or_insert(game);
+
+            Ok(())
+        }
+
+        // writes an attribute in the calling game's namespace. attributes stay with the token
+        // when it changes hands
+        #[ink(message)]
+        pub fn set_attribute(&mut self, seed: Seed, key: String, value: Vec<u8>) -> Result<()> {
+            let namespace = self.env().caller();
+
+            if !self.is_game(namespace) {
+                return Err(Error::NotAllowed);
+            }
+
+            self.assert_exists(seed)?;
+
+            if key.len() > MAX_ATTRIBUTE_KEY_LENGTH as usize
+                || value.len() > MAX_ATTRIBUTE_VALUE_LENGTH as usize
+            {
+                return Err(Error::AttributeTooLarge);
+            }
+
+            let mut attributes = self.attributes_of(seed);
+
+            match attributes
+                .iter_mut()
+                .find(|attribute| attribute.namespace == namespace && attribute.key == key)
+            {
+                Some(attribute) => attribute.value = value.clone(),
+                None => {
+                    let count = attributes
+                        .iter()
+                        .filter(|attribute| attribute.namespace == namespace)
+                        .count();
+
+                    if count >= MAX_ATTRIBUTES_PER_GAME as usize {
+                        return Err(Error::TooManyAttributes);
+                    }
+
+                    attributes.push(Attribute {
+                        namespace,
+                        key: key.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+
+            self.attributes.insert(seed, attributes);
+
+            self.env().emit_event(AttributeSet {
+                seed,
+                namespace,
+                key,
+                value,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn attribute(&self, seed: Seed, namespace: AccountId, key: String) -> Option<Vec<u8>> {
+            self.attributes.get(&seed).and_then(|attributes| {
+                attributes
+                    .iter()
+                    .find(|attribute| attribute.namespace == namespace && attribute.key == key)
+                    .map(|attribute| attribute.value.clone())
+            })
+        }
+
+        #[ink(message)]
+        pub fn attributes_of(&self, seed: Seed) -> Vec<Attribute> {
+            self.attributes.get(&seed).cloned().unwrap_or(Vec::new())
+        }
+
+        // rating in the current season, or in the last one if none is running
+        #[ink(message)]
+        pub fn rating_of(&self, account: AccountId) -> Rating {
+            self.ratings
+                .get(&(self.season, account))
+                .cloned()
+                .unwrap_or(elo::INITIAL)
+        }
+
+        // tokens keep their rating across seasons
+        #[ink(message)]
+        pub fn token_rating_of(&self, seed: Seed) -> Rating {
+            self.token_ratings
+                .get(&seed)
+                .cloned()
+                .unwrap_or(elo::INITIAL)
+        }
+
+        // best rated accounts of a season, best first. ended seasons use their final standings
+        #[ink(message)]
+        pub fn top_ratings(&self, season: SeasonId, n: u32) -> Vec<(AccountId, Rating)> {
+            let standings = match self.standings.get(&season) {
+                Some(standings) => standings.clone(),
+                None => self.live_standings(season),
+            };
+
+            standings.into_iter().take(n as usize).collect()
+        }
+
+        #[ink(message)]
+        pub fn current_season(&self) -> Option<SeasonId> {
+            if self.season_active {
+                Some(self.season)
+            } else {
+                None
+            }
+        }
+
+        // every season starts everyone off at the initial rating
+        #[ink(message)]
+        pub fn start_season(&mut self) -> Result<SeasonId> {
+            self.assert_admin()?;
+
+            if self.season_active {
+                return Err(Error::SeasonInProgress);
+            }
+
+            self.season += 1;
+            self.season_active = true;
+
+            self.env().emit_event(SeasonStarted {
+                season: self.season,
+            });
+
+            Ok(self.season)
+        }
+
+        // freezes the final standings and announces the top places, for the admin to reward
+        #[ink(message)]
+        pub fn end_season(&mut self) -> Result<()> {
+            self.assert_admin()?;
+
+            if !self.season_active {
+                return Err(Error::NoActiveSeason);
+            }
+
+            let season = self.season;
+            let standings = self.live_standings(season);
+            self.season_active = false;
+
+            self.env().emit_event(SeasonEnded { season });
+
+            for (idx, (account, rating)) in standings
+                .iter()
+                .take(SEASON_REWARD_PLACES as usize)
+                .enumerate()
+            {
+                self.env().emit_event(SeasonReward {
+                    season,
+                    rank: idx as u32 + 1,
+                    account: *account,
+                    rating: *rating,
+                });
+            }
+
+            self.standings.insert(season, standings);
+
+            Ok(())
+        }
+
+        fn assert_exists(&self, seed: Seed) -> Result<()> {
+            if !self.exists(seed) {
+                return Err(Error::TokenNotFound);
+            }
+
+            Ok(())
+        }
+
+        fn assert_unlocked(&self, seed: Seed) -> Result<()> {
+            if self.is_locked(seed) {
+                return Err(Error::TokenLocked);
+            }
+
+            Ok(())
+        }
+
+        fn assert_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAllowed);
+            }
+
+            Ok(())
+        }
+
+        // checks every item of a batch upfront, so that nothing is applied unless all of it can be
+        fn assert_batch<T, F>(&self, items: &[T], check: F) -> BatchResult<()>
+        where
+            T: Copy,
+            F: Fn(usize, T) -> Result<()>,
+        {
+            if items.len() > self.max_batch_size as usize {
+                return Err(BatchError::at(
+                    self.max_batch_size as usize,
+                    Error::BatchTooLarge,
+                ));
+            }
+
+            items.iter().enumerate().try_for_each(|(idx, item)| {
+                check(idx, *item).map_err(|error| BatchError::at(idx, error))
+            })
+        }
+
+        fn assert_minter(&self) -> Result<()> {
+            if !self.is_minter(self.env().caller()) {
+                return Err(Error::NotAllowed);
+            }
+
+            Ok(())
+        }
+
+        fn assert_valid_account(&self, account: &AccountId) -> Result<()> {
+            if *account == AccountId::from([0x0; 32]) {
+                return Err(Error::InvalidAddress);
+            }
+
+            Ok(())
+        }
+
+        fn is_player(&self, account: &AccountId, seed: Seed) -> bool {
+            self.owner_of(seed) == *account || self.user_of(seed) == Some(*account)
+        }
+
+        fn assert_owner_or_approved(&self, seed: Seed) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(seed);
+            let current_approver = self.approved.get(&seed);
+
+            if !(owner == caller
+                || current_approver == Some(&caller)
+                || self.is_approved_for_all(owner, caller))
+            {
+                return Err(Error::NotAllowed);
+            }
+
+            Ok(())
+        }
+
+        fn assert_trade_ownership(&self, trade: &Trade) -> Result<()> {
+            for seed in trade.offered.iter() {
+                self.assert_exists(*seed)?;
+                if self.owner_of(*seed) != trade.proposer {
+                    return Err(Error::NotOwner);
+                }
+            }
+
+            for seed in trade.requested.iter() {
+                self.assert_exists(*seed)?;
+                if self.owner_of(*seed) != trade.counterparty {
+                    return Err(Error::NotOwner);
+                }
+            }
+
+            Ok(())
+        }
+
+        fn exists(&self, seed: Seed) -> bool {
+            self.seeds.contains_key(&seed)
+        }
+
+        // pending eggs reserve their seed until they hatch, and burned seeds are gone for good
+        fn assert_mintable(&self, seed: Seed) -> Result<()> {
+            if self.burned.contains_key(&seed) {
+                return Err(Error::TokenBurned);
+            }
+
+            if self.exists(seed) || self.eggs.contains_key(&seed) {
+                return Err(Error::TokenAlreadyExists);
+            }
+
+            Ok(())
+        }
+
+        fn impl_transfer_from(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            seed: Seed,
+        ) -> Result<()> {
+            self.assert_exists(seed)?;
+            self.assert_valid_account(to)?;
+
+            if self.owner_of(seed) != *from {
+                return Err(Error::NotOwner);
+            }
+
+            self.assert_unlocked(seed)?;
+
+            // an offer only stands while the borrower holds the token
+            self.clear_loan_offer(seed);
+            self.move_token(from, to, seed)
+        }
+
+        // moves a token that has already been checked, dropping what its old owner set up
+        fn move_token(&mut self, from: &AccountId, to: &AccountId, seed: Seed) -> Result<()> {
+            self.impl_clear_approval(seed)?;
+            self.clear_listing(seed);
+            self.clear_user(seed);
+
+            let pokemon = self.remove_token_from(from, seed)?;
+            self.add_token_to(to, seed, pokemon)?;
+
+            self.env().emit_event(Transfer {
+                from: Some(*from),
+                to: Some(*to),
+                seed,
+            });
+
+            Ok(())
+        }
+
+        fn impl_mint(&mut self, to: &AccountId, seed: Seed, pokemon: PokemonId) -> Result<()> {
+            self.assert_mintable(seed)?;
+            self.add_token_to(to, seed, pokemon)?;
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(*to),
+                seed,
+            });
+
+            Ok(())
+        }
+
+        // samples the pokemon for a mint. after `pity_threshold - 1` non-rare mints in a row, the
+        // next one is sampled from rare pokemon only
+        fn roll_pokemon(&self, account: &AccountId, seed: Seed) -> Result<PokemonId> {
+            if self.pity_due(account) {
+                rng::sample_weighted(seed, |population| {
+                    if population < rng::RARE_POPULATION {
+                        population
+                    } else {
+                        0
+                    }
+                })
+            } else {
+                rng::sample(seed).map_err(|_| Error::InvalidSeed)
+            }
+        }
+
+        fn impl_award_badge(&mut self, account: &AccountId, badge: BadgeId) -> Result<()> {
+            if !self.badges.contains_key(&badge) {
+                return Err(Error::BadgeNotFound);
+            }
+
+            let badges = self.account_badges.entry(*account).or_insert(Vec::new());
+
+            if badges.contains(&badge) {
+                return Err(Error::BadgeAlreadyAwarded);
+            }
+
+            badges.push(badge);
+
+            self.env().emit_event(BadgeAwarded {
+                account: *account,
+                badge,
+            });
+
+            Ok(())
+        }
+
+        fn register_pokemon(&mut self, account: &AccountId, pokemon: PokemonId) {
+            let mut dex = self.pokedexes.get(account).cloned().unwrap_or_default();
+            let before = pokedex::milestones(&dex);
+
+            pokedex::register(&mut dex, pokemon);
+            self.pokedexes.insert(*account, dex);
+
+            for milestone in pokedex::milestones(&dex) {
+                if !before.contains(&milestone) {
+                    self.env().emit_event(Achievement {
+                        account: *account,
+                        milestone,
+                    });
+
+                    if let Some(badge) = self.milestone_badges.get(&milestone).cloned() {
+                        // the account may have been awarded the badge by hand already
+                        let _ = self.impl_award_badge(account, badge);
+                    }
+                }
+            }
+        }
+
+        fn pity_due(&self, account: &AccountId) -> bool {
+            let threshold = self.pity_threshold;
+
+            threshold > 0 && self.pity_of(*account) + 1 >= threshold
+        }
+
+        fn record_pity(&mut self, account: &AccountId, pokemon: PokemonId) {
+            let pity = if rng::is_rare(pokemon) {
+                0
+            } else {
+                self.pity_of(*account) + 1
+            };
+
+            self.pity.insert(*account, pity);
+        }
+
+        // what's left of the global per block limit, whoever is minting
+        fn block_mints_remaining(&self) -> Option<u32> {
+            let now = self.env().block_number();
+
+            self.mint_limits
+                .per_block
+                .map(|max| match self.block_mints {
+                    (block, count) if block == now => max.saturating_sub(count),
+                    _ => max,
+                })
+        }
+
+        fn record_mints(&mut self, account: &AccountId, amount: u32) {
+            let now = self.env().block_number();
+
+            *self.account_mints.entry(*account).or_insert(0) += amount;
+
+            if let Some((_, window)) = self.mint_limits.per_window {
+                // windows are aligned to multiples of their length
+                let start = now - now % window.max(1);
+                let entry = self.window_mints.entry(*account).or_insert((start, 0));
+
+                if entry.0 != start {
+                    *entry = (start, 0);
+                }
+                entry.1 += amount;
+            }
+
+            self.block_mints = match self.block_mints {
+                (block, count) if block == now => (block, count + amount),
+                _ => (now, amount),
+            };
+        }
+
+        fn live_standings(&self, season: SeasonId) -> Vec<(AccountId, Rating)> {
+            let mut standings: Vec<(AccountId, Rating)> = self
+                .season_players
+                .get(&season)
+                .map(|players| {
+                    players
+                        .iter()
+                        .map(|account| {
+                            let rating = self.ratings.get(&(season, *account)).cloned();
+                            (*account, rating.unwrap_or(elo::INITIAL))
+                        })
+                        .collect()
+                })
+                .unwrap_or(Vec::new());
+
+            standings.sort_by(|a, b| b.1.cmp(&a.1));
+            standings
+        }
+
+        // updates ratings after a ranked match. account ratings only change during a season
+        fn record_match(&mut self, winner: (AccountId, Seed), loser: (AccountId, Seed)) {
+            let (winner_rating, loser_rating) = elo::update(
+                self.token_rating_of(winner.1),
+                self.token_rating_of(loser.1),
+            );
+            self.token_ratings.insert(winner.1, winner_rating);
+            self.token_ratings.insert(loser.1, loser_rating);
+
+            if !self.season_active {
+                return;
+            }
+
+            let season = self.season;
+            let (winner_rating, loser_rating) =
+                elo::update(self.rating_of(winner.0), self.rating_of(loser.0));
+
+            for (account, rating) in [(winner.0, winner_rating), (loser.0, loser_rating)].iter() {
+                if self.ratings.insert((season, *account), *rating).is_none() {
+                    self.season_players
+                        .entry(season)
+                        .or_insert(Vec::new())
+                        .push(*account);
+                }
+            }
+        }
+
+        // the account that entered the token into the tournament, which is who it plays for
+        fn registrant_of(&self, tournament: &Tournament, seed: Seed) -> AccountId {
+            tournament
+                .registrants
+                .iter()
+                .find(|(entrant, _)| *entrant == seed)
+                .map_or_else(|| self.owner_of(seed), |(_, account)| *account)
+        }
+
+        // pays out both stakes, then releases both pokemon. only staked matches count towards the
+        // ladder, so nobody can farm ratings by challenging their own accounts for free
+        fn settle_challenge(
+            &mut self,
+            id: ChallengeId,
+            challenge: &Challenge,
+            winner: (AccountId, Seed),
+            loser: (AccountId, Seed),
+        ) -> Result<()> {
+            let payout = challenge.stake * 2;
+            self.pay(&winner.0, payout)?;
+
+            let contract = self.env().account_id();
+            self.challenges.take(&id);
+            self.impl_unlock(challenge.challenger_seed, LockReason::Challenge, contract);
+            self.impl_unlock(challenge.opponent_seed, LockReason::Challenge, contract);
+
+            if challenge.stake > 0 {
+                self.record_match(winner, loser);
+            }
+
+            self.env().emit_event(ChallengeSettled {
+                id,
+                winner: winner.0,
+                payout,
+            });
+
+            Ok(())
+        }
+
+        // replaces any lock the locker already has on the token for the same reason. expired
+        // locks are dropped along the way
+        fn impl_lock(
+            &mut self,
+            seed: Seed,
+            reason: LockReason,
+            locker: AccountId,
+            until: BlockNumber,
+        ) {
+            let mut locks: Vec<Lock> = self
+                .locks_of(seed)
+                .into_iter()
+                .filter(|lock| lock.reason != reason || lock.locker != locker)
+                .collect();
+            locks.push(Lock {
+                reason,
+                locker,
+                until,
+            });
+            self.locks.insert(seed, locks);
+
+            self.env().emit_event(Locked {
+                seed,
+                reason,
+                locker,
+                until,
+            });
+        }
+
+        // returns whether there was an active lock to lift
+        fn impl_unlock(&mut self, seed: Seed, reason: LockReason, locker: AccountId) -> bool {
+            let mut locks = self.locks_of(seed);
+            let count = locks.len();
+            locks.retain(|lock| lock.reason != reason || lock.locker != locker);
+
+            if locks.len() == count {
+                return false;
+            }
+
+            if locks.is_empty() {
+                self.locks.take(&seed);
+            } else {
+                self.locks.insert(seed, locks);
+            }
+
+            self.env().emit_event(Unlocked {
+                seed,
+                reason,
+                locker,
+            });
+
+            true
+        }
+
+        fn clear_listing(&mut self, seed: Seed) {
+            if self.listings.take(&seed).is_some() {
+                self.env().emit_event(Delisted { seed });
+            }
+        }
+
+        // funded loans are left alone, their collateral is held by the contract
+        fn clear_loan_offer(&mut self, seed: Seed) {
+            if self
+                .loan_of(seed)
+                .map_or(false, |loan| loan.lender.is_none())
+            {
+                self.loans.take(&seed);
+                self.env().emit_event(LoanCancelled { seed });
+            }
+        }
+
+        fn clear_user(&mut self, seed: Seed) {
+            if self.users.take(&seed).is_some() {
+                self.env().emit_event(UpdateUser {
+                    seed,
+                    user: None,
+                    expires: 0,
+                });
+            }
+        }
+
+        fn daycare_experience(&self, stay: &DaycareStay) -> u32 {
+            let blocks = self.env().block_number().saturating_sub(stay.since);
+
+            blocks
+                .saturating_mul(DAYCARE_EXPERIENCE_PER_BLOCK)
+                .min(DAYCARE_EXPERIENCE_CAP)
+        }
+
+        // experience is capped at what level 100 takes
+        fn add_experience(&mut self, seed: Seed, amount: u32) {
+            let level = self.level_of(seed);
+            let experience = self
+                .experience_of(seed)
+                .saturating_add(amount)
+                .min(battle::experience_for(battle::MAX_LEVEL));
+
+            self.experience.insert(seed, experience);
+
+            let new_level = battle::level(experience);
+
+            if new_level > level {
+                self.env().emit_event(LevelUp {
+                    seed,
+                    level: new_level,
+                });
+            }
+        }
+
+        // ranked battles use the pokemon's levels and the winner gains experience based on the
+        // level of the pokemon it defeated
+        fn resolve_battle<E: scale::Encode>(
+            &mut self,
+            attacker: Seed,
+            defender: Seed,
+            entropy: E,
+            ranked: bool,
+        ) -> Result<BattleOutcome> {
+            if attacker == defender {
+                return Err(Error::InvalidOpponent);
+            }
+
+            let seed = self
+                .env()
+                .hash_encoded::<Blake2x256, _>(&(attacker, defender, entropy));
+            let combatant = |seed: Seed| battle::Combatant {
+                pokemon: self.pokemon_of(seed),
+                ivs: rng::ivs(seed),
+                level: if ranked {
+                    self.level_of(seed)
+                } else {
+                    battle::CASUAL_LEVEL
+                },
+            };
+            let (attacking, defending) = (combatant(attacker), combatant(defender));
+
+            let outcome = battle::resolve(attacking, defending, seed)?;
+            let (winner, loser_level) = if outcome.attacker_won {
+                (attacker, defending.level)
+            } else {
+                (defender, attacking.level)
+            };
+
+            self.env().emit_event(BattleResult {
+                attacker,
+                defender,
+                winner,
+                turns: outcome.turns,
+                log_hash: outcome.log_hash,
+            });
+
+            if ranked {
+                self.add_experience(winner, battle::experience_yield(loser_level));
+            }
+
+            Ok(BattleOutcome {
+                attacker,
+                defender,
+                winner,
+                turns: outcome.turns,
+                log_hash: outcome.log_hash,
+            })
+        }
+
+        fn impl_approve(&mut self, approved: &AccountId, seed: Seed) {
+            self.approved.insert(seed, *approved);
+
+            self.env().emit_event(Approval {
+                seed,
+                account: Some(*approved),
+            });
+        }
+
+        fn impl_clear_approval(&mut self, seed: Seed) -> Result<()> {
+            if !self.approved.contains_key(&seed) {
+                return Ok(());
+            }
+
+            match self.approved.take(&seed) {
+                Some(_) => {
+                    self.env().emit_event(Approval {
+                        seed,
+                        account: None,
+                    });
+                    Ok(())
+                }
+                None => Err(Error::CannotRemove),
+            }
+        }
+
+        fn pay(&mut self, to: &AccountId, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Ok(());
+            }
+
+            self.env()
+                .transfer(*to, amount)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        // pays out a sale, deducting royalties for the configured receiver
+        fn pay_sale(&mut self, seed: Seed, seller: &AccountId, price: Balance) -> Result<()> {
+            let (receiver, royalty) = self.royalty_info(seed, price);
+
+            self.pay(&receiver, royalty)?;
+            self.pay(seller, price - royalty)?;
+
+            if royalty > 0 {
+                self.env().emit_event(RoyaltyPaid {
+                    seed,
+                    receiver,
+                    amount: royalty,
+                });
+            }
+
+            Ok(())
+        }
+
+        fn remove_token_from(&mut self, from: &AccountId, seed: Seed) -> Result<PokemonId> {
+            // remove entry from seeds hash
+            let seeds_entry = match self.seeds.entry(seed) {
+                Entry::Vacant(_) => return Err(Error::TokenNotFound),
+                Entry::Occupied(entry) => entry,
+            };
+            let (_, (_, pokemon)) = seeds_entry.remove_entry();
+
+            // remove entry from owner's vec of owned seeds
+            self.owners
+                .get_mut(from)
+                .ok_or(Error::NotOwner)?
+                .retain(|&owned_seed| owned_seed != seed);
+
+            Ok(pokemon)
+        }
+
+        fn add_token_to(&mut self, to: &AccountId, seed: Seed, id: PokemonId) -> Result<()> {
+            let seeds_entry = match self.seeds.entry(seed) {
+                Entry::Vacant(entry) => entry,
+                Entry::Occupied(_) => return Err(Error::TokenAlreadyExists),
+            };
+
+            if *to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotAllowed);
+            }
+
+            seeds_entry.insert((*to, id));
+
+            self.owners.entry(*to).or_insert(Vec::new()).push(seed);
+
+            // escrowed tokens don't count towards the contract's own pokedex
+            if *to != self.env().account_id() {
+                self.register_pokemon(to, id);
+            }
+
+            Ok(())
+        }
+    }
+
+    // forwards to the inherent messages, which keep their selectors for existing clients
+    impl PokeNftInterface for PokeNFT {
+        #[ink(message)]
+        fn owner_of(&self, seed: Seed) -> AccountId {
+            PokeNFT::owner_of(self, seed)
+        }
+
+        #[ink(message)]
+        fn pokemon_of(&self, seed: Seed) -> PokemonId {
+            PokeNFT::pokemon_of(self, seed)
+        }
+
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, seed: Seed) -> Result<()> {
+            PokeNFT::transfer_from(self, from, to, seed)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use ink_env::test::{default_accounts, push_execution_context, recorded_events};
+        use ink_env::{self, DefaultEnvironment};
+        use ink_lang as ink;
+        use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+        macro_rules! zero_account {
+            () => {
+                AccountId::from([0x0; 32])
+            };
+        }
+
+        macro_rules! alice {
+            () => {
+                default_accounts::<DefaultEnvironment>().unwrap().alice
+            };
+        }
+
+        macro_rules! bob {
+            () => {
+                default_accounts::<DefaultEnvironment>().unwrap().bob
+            };
+        }
+
+        macro_rules! charlie {
+            () => {
+                default_accounts::<DefaultEnvironment>().unwrap().charlie
+            };
+        }
+
+        macro_rules! use_account {
+            ($account:expr) => {
+                use_account!($account, 1000000);
+            };
+            ($account:expr, $value:expr) => {
+                let mut data =
+                    ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4]));
+                data.push_arg(&$account);
+
+                push_execution_context::<DefaultEnvironment>(
+                    $account,
+                    contract_id(),
+                    1000000,
+                    $value,
+                    data,
+                );
+            };
+        }
+
+        macro_rules! seed {
+            ($seed:expr) => {
+                [$seed; 32]
+            };
+        }
+
+        macro_rules! advance_blocks {
+            ($blocks:expr) => {
+                for _ in 0..$blocks {
+                    ink_env::test::advance_block::<DefaultEnvironment>()
+                        .expect("Cannot advance block");
+                }
+            };
+        }
+
+        fn contract_id() -> AccountId {
+            ink_env::account_id::<ink_env::DefaultEnvironment>().unwrap_or([0x0; 32].into())
+        }
+
+        fn account_balance(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        fn set_balance(account: AccountId, balance: Balance) {
+            ink_env::test::set_account_balance::<DefaultEnvironment>(account, balance)
+                .expect("Cannot set account balance")
+        }
+
+        const DEV_VOUCHER_KEY: [u8; 32] = [0x2a; 32];
+        const CHALLENGE_SECRET: [u8; 32] = [0x07; 32];
+        const OPPONENT_ENTROPY: [u8; 32] = [0x01; 32];
+
+        fn commitment(secret: [u8; 32]) -> [u8; 32] {
+            let mut output = [0u8; 32];
+            ink_env::hash_bytes::<Blake2x256>(&secret, &mut output);
+            output
+        }
+
+        fn voucher_signer() -> AccountId {
+            let secp = Secp256k1::new();
+            let secret = SecretKey::from_slice(&DEV_VOUCHER_KEY).unwrap();
+            let public_key = PublicKey::from_secret_key(&secp, &secret).serialize();
+            let mut signer = [0u8; 32];
+            ink_env::hash_bytes::<Blake2x256>(&public_key, &mut signer);
+
+            AccountId::from(signer)
+        }
+
+        fn sign_voucher(voucher: &Voucher) -> Vec<u8> {
+            sign_voucher_for(contract_id(), voucher)
+        }
+
+        fn sign_voucher_for(contract: AccountId, voucher: &Voucher) -> Vec<u8> {
+            let secp = Secp256k1::new();
+            let secret = SecretKey::from_slice(&DEV_VOUCHER_KEY).unwrap();
+            let mut hash = [0u8; 32];
+            ink_env::hash_encoded::<Blake2x256, _>(
+                &(VOUCHER_DOMAIN, contract, *voucher),
+                &mut hash,
+            );
+
+            let message = Message::from_slice(&hash).unwrap();
+            let (recovery_id, signature) =
+                secp.sign_recoverable(&message, &secret).serialize_compact();
+
+            let mut result = signature.to_vec();
+            result.push(recovery_id.to_i32() as u8);
+            result
+        }
+
+        // builds an allowlist tree, returning its root and a proof for each leaf
+        fn merkle_tree(leaves: &[merkle::Node]) -> (merkle::Node, Vec<Vec<merkle::Node>>) {
+            let mut proofs = vec![Vec::new(); leaves.len()];
+            let mut positions: Vec<usize> = (0..leaves.len()).collect();
+            let mut level = leaves.to_vec();
+
+            while level.len() > 1 {
+                for (leaf, position) in positions.iter_mut().enumerate() {
+                    let sibling = *position ^ 1;
+                    if sibling < level.len() {
+                        proofs[leaf].push(level[sibling]);
+                    }
+                    *position /= 2;
+                }
+
+                level = level
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [a, b] => merkle::hash_pair(*a, *b),
+                        [a] => *a,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+            }
+
+            (level[0], proofs)
+        }
+
+        fn node(hex: &str) -> merkle::Node {
+            let mut node = merkle::Node::default();
+            for (i, byte) in node.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+            }
+            node
+        }
+
+        fn get_event(idx: usize) -> Event {
+            let raw_event = recorded_events()
+                .nth(idx)
+                .expect(&format!("No event found"));
+
+            <Event as scale::Decode>::decode(&mut &raw_event.data[..])
+                .expect("Invalid contract Event")
+        }
+
+        fn last_event() -> Event {
+            get_event(recorded_events().count() - 1)
+        }
+
+        #[ink::test]
+        fn mint() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(10)).unwrap();
+
+            assert_eq!(nft.seeds.get(&seed!(10)), Some(&(alice!(), 16)));
+
+            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
+                assert_eq!(from, None);
+                assert_eq!(to, Some(alice!()));
+                assert_eq!(seed, seed!(10));
+            } else {
+                panic!("Expected to find Transfer event");
+            };
+        }
+
+        #[ink::test]
+        fn cant_mint_twice() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(1)).unwrap();
+
+            let result = nft.mint(seed!(1));
+
+            assert_eq!(result, Err(Error::TokenAlreadyExists));
+        }
+
+        #[ink::test]
+        fn transfer() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+
+            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
+                assert_eq!(from, Some(alice!()));
+                assert_eq!(to, Some(bob!()));
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find Transfer event");
+            };
+        }
+
+        #[ink::test]
+        fn transfer_someone_elses_token() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            let result = nft.transfer(alice!(), seed!(0));
+
+            assert_eq!(result, Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn approved_transfers_from() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.approve(bob!(), seed!(0)).unwrap();
+
+            use_account!(bob!());
+            let result = nft.transfer_from(alice!(), charlie!(), seed!(0));
+
+            assert_eq!(result, Ok(()));
+            assert_eq!(nft.owner_of(seed!(0)), charlie!());
+        }
+
+        #[ink::test]
+        fn unapproved_transfers_from() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            let result = nft.transfer_from(alice!(), charlie!(), seed!(0));
+
+            assert_eq!(result, Err(Error::NotAllowed));
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+        }
+
+        #[ink::test]
+        fn transfer_non_existing_token() {
+            let mut nft = PokeNFT::new();
+
+            let result = nft.transfer(bob!(), seed!(0));
+
+            assert_eq!(result, Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn transfer_removes_approval() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.approve(bob!(), seed!(0)).unwrap();
+
+            nft.transfer(charlie!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.get_approved(seed!(0)), None);
+        }
+
+        #[ink::test]
+        fn balance_of() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.balance_of(alice!()), 2);
+            assert_eq!(nft.balance_of(bob!()), 0);
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.balance_of(alice!()), 1);
+            assert_eq!(nft.balance_of(bob!()), 1);
+
+            use_account!(bob!());
+
+            nft.transfer(alice!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.balance_of(alice!()), 2);
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn tokens_of() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            let empty: Vec<Seed> = vec![];
+
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(0), seed!(1)]);
+            assert_eq!(nft.tokens_of(bob!()), empty);
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(1)]);
+            assert_eq!(nft.tokens_of(bob!()), vec![seed!(0)]);
+        }
+
+        #[ink::test]
+        fn owner_of() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            assert_eq!(nft.owner_of(seed!(1)), alice!());
+            assert_eq!(nft.owner_of(seed!(2)), zero_account!());
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.owner_of(seed!(1)), alice!());
+            assert_eq!(nft.owner_of(seed!(2)), zero_account!());
+        }
+
+        #[ink::test]
+        fn pokemon_of() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.pokemon_of(seed!(0)), 72);
+            assert_eq!(nft.pokemon_of(seed!(1)), 19);
+            assert_eq!(nft.pokemon_of(seed!(2)), 0);
+        }
+
+        type Event = <PokeNFT as ::ink_lang::BaseEvent>::Type;
+
+        #[ink::test]
+        fn approved() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(nft.get_approved(seed!(0)), None);
+
+            nft.approve(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+
+            if let Event::Approval(Approval { seed, account }) = get_event(1) {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(account, Some(bob!()));
+            } else {
+                panic!("Expected last event to be an Approval");
+            }
+        }
+
+        #[ink::test]
+        fn approve_zero_address() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(
+                nft.approve(zero_account!(), seed!(0)),
+                Err(Error::InvalidAddress)
+            );
+        }
+
+        #[ink::test]
+        fn approve_while_not_owner() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.approve(bob!(), seed!(0)), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn approve_non_existing_token() {
+            let mut nft = PokeNFT::new();
+            assert_eq!(nft.get_approved(seed!(0)), None);
+
+            let result = nft.approve(bob!(), seed!(0));
+
+            assert_eq!(result, Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn approve_while_approved() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.approve(bob!(), seed!(0)).unwrap();
+
+            use_account!(bob!());
+            let result = nft.approve(alice!(), seed!(0));
+
+            assert_eq!(result, Ok(()));
+        }
+
+        #[ink::test]
+        fn clear_approval() {
+            let mut nft = PokeNFT::new();
+
+            nft.mint(seed!(0)).unwrap();
+
+            nft.approve(bob!(), seed!(0)).unwrap();
+            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+
+            nft.clear_approval(seed!(0)).unwrap();
+
+            assert_eq!(nft.get_approved(seed!(0)), None);
+        }
+
+        #[ink::test]
+        fn approver_removes_itself() {
+            let mut nft = PokeNFT::new();
+
+            nft.mint(seed!(0)).unwrap();
+
+            nft.approve(bob!(), seed!(0)).unwrap();
+            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+
+            use_account!(bob!());
+            nft.clear_approval(seed!(0)).unwrap();
+
+            assert_eq!(nft.get_approved(seed!(0)), None);
+        }
+
+        #[ink::test]
+        fn other_user_fails_to_remove_approver() {
+            let mut nft = PokeNFT::new();
+
+            nft.mint(seed!(0)).unwrap();
+
+            nft.approve(bob!(), seed!(0)).unwrap();
+            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+
+            use_account!(charlie!());
+            let result = nft.clear_approval(seed!(0));
+
+            assert_eq!(result, Err(Error::NotAllowed));
+            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+        }
+
+        #[ink::test]
+        fn approved_for_all() {
+            let mut nft = PokeNFT::new();
+
+            assert_eq!(nft.is_approved_for_all(alice!(), bob!()), false);
+
+            nft.set_approval_for_all(bob!(), true).unwrap();
+
+            assert_eq!(nft.is_approved_for_all(alice!(), bob!()), true);
+        }
+
+        #[ink::test]
+        fn operator_can_transfer_from_owner() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_approval_for_all(bob!(), true).unwrap();
+
+            use_account!(bob!());
+            nft.transfer_from(alice!(), bob!(), seed!(0)).unwrap();
+        }
+
+        #[ink::test]
+        fn breed() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            let egg = nft.breed(seed!(0), seed!(1)).unwrap();
+
+            assert_eq!(egg[0], seed!(0)[0]);
+            assert_eq!(egg[1], seed!(1)[1]);
+            assert_eq!(nft.eggs_of(alice!()), vec![egg]);
+            assert_eq!(nft.breeding_cooldown_of(seed!(0)), BREEDING_COOLDOWN);
+            assert_eq!(nft.breeding_cooldown_of(seed!(1)), BREEDING_COOLDOWN);
+            assert_eq!(nft.owner_of(egg), zero_account!());
+
+            if let Event::Bred(Bred {
+                parent_a,
+                parent_b,
+                egg: bred,
+                hatches_at,
+            }) = last_event()
+            {
+                assert_eq!(parent_a, seed!(0));
+                assert_eq!(parent_b, seed!(1));
+                assert_eq!(bred, egg);
+                assert_eq!(hatches_at, HATCH_DELAY);
+            } else {
+                panic!("Expected to find Bred event");
+            };
+        }
+
+        #[ink::test]
+        fn hatch() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            let egg = nft.breed(seed!(0), seed!(1)).unwrap();
+
+            assert_eq!(nft.hatch(egg), Err(Error::EggNotReady));
+
+            advance_blocks!(HATCH_DELAY);
+            nft.hatch(egg).unwrap();
+
+            // Tentacool and Rattata are both base forms
+            assert!([72, 19].contains(&nft.pokemon_of(egg)));
+            assert_eq!(nft.owner_of(egg), alice!());
+            assert_eq!(nft.egg_of(egg), None);
+            assert_eq!(nft.hatch(egg), Err(Error::EggNotFound));
+        }
+
+        #[ink::test]
+        fn mint_pending_egg() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            let egg = nft.breed(seed!(0), seed!(1)).unwrap();
+
+            use_account!(bob!());
+            assert_eq!(nft.mint(egg), Err(Error::TokenAlreadyExists));
+            assert_eq!(nft.owner_of(egg), zero_account!());
+
+            advance_blocks!(HATCH_DELAY);
+
+            use_account!(alice!());
+            nft.hatch(egg).unwrap();
+
+            assert_eq!(nft.owner_of(egg), alice!());
+        }
+
+        #[ink::test]
+        fn hatch_someone_elses_egg() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            let egg = nft.breed(seed!(0), seed!(1)).unwrap();
+            advance_blocks!(HATCH_DELAY);
+
+            use_account!(bob!());
+
+            assert_eq!(nft.hatch(egg), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn breeding_cooldown() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.mint(seed!(2)).unwrap();
+            nft.breed(seed!(0), seed!(1)).unwrap();
+
+            assert_eq!(nft.breed(seed!(0), seed!(2)), Err(Error::BreedingCooldown));
+
+            advance_blocks!(BREEDING_COOLDOWN);
+
+            assert!(nft.breed(seed!(0), seed!(2)).is_ok());
+        }
+
+        #[ink::test]
+        fn breed_with_itself() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(nft.breed(seed!(0), seed!(0)), Err(Error::IncompatiblePair));
+        }
+
+        #[ink::test]
+        fn breed_someone_elses_token() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.breed(seed!(1), seed!(0)), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn operator_can_breed() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.set_approval_for_all(bob!(), true).unwrap();
+
+            use_account!(bob!());
+            let egg = nft.breed(seed!(0), seed!(1)).unwrap();
+
+            assert_eq!(nft.egg_of(egg).map(|egg| egg.owner), Some(alice!()));
+        }
+
+        #[test]
+        fn incompatible_species() {
+            // Ditto can't breed with itself, legendaries can't breed at all
+            assert!(!breeding::is_compatible(132, 132));
+            assert!(!breeding::is_compatible(150, 1));
+            assert!(!breeding::is_compatible(1, 0));
+            assert!(breeding::is_compatible(132, 6));
+        }
+
+        #[test]
+        fn offspring_is_a_base_form() {
+            assert_eq!(breeding::offspring(6, 132, [0; 32]), 4);
+            assert_eq!(breeding::offspring(132, 9, [0; 32]), 7);
+            assert_eq!(breeding::offspring(3, 9, [0; 32]), 1);
+            assert_eq!(breeding::offspring(3, 9, [1; 32]), 7);
+        }
+
+        #[ink::test]
+        fn list() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            nft.list(seed!(0), 100).unwrap();
+
+            let listing = Listing {
+                seller: alice!(),
+                price: 100,
+            };
+            assert_eq!(nft.listing_of(seed!(0)), Some(listing));
+            assert_eq!(nft.listings(0, 10), vec![(seed!(0), listing)]);
+            assert_eq!(nft.listings(1, 10), Vec::<(Seed, Listing)>::new());
+
+            if let Event::Listed(Listed {
+                seed,
+                seller,
+                price,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(seller, alice!());
+                assert_eq!(price, 100);
+            } else {
+                panic!("Expected to find Listed event");
+            };
+        }
+
+        #[ink::test]
+        fn list_someone_elses_token() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.list(seed!(0), 100), Err(Error::NotAllowed));
+            assert_eq!(nft.listing_of(seed!(0)), None);
+        }
+
+        #[ink::test]
+        fn delist() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.list(seed!(0), 100).unwrap();
+
+            nft.delist(seed!(0)).unwrap();
+
+            assert_eq!(nft.listing_of(seed!(0)), None);
+            assert_eq!(nft.delist(seed!(0)), Err(Error::NotListed));
+        }
+
+        #[ink::test]
+        fn buy() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.approve(charlie!(), seed!(0)).unwrap();
+            nft.list(seed!(0), 100).unwrap();
+            set_balance(contract_id(), 100);
+            let seller_balance = account_balance(alice!());
+
+            use_account!(bob!(), 100);
+            nft.buy(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.listing_of(seed!(0)), None);
+            assert_eq!(nft.get_approved(seed!(0)), None);
+            assert_eq!(account_balance(alice!()), seller_balance + 100);
+
+            if let Event::Sold(Sold {
+                seed,
+                seller,
+                buyer,
+                price,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(seller, alice!());
+                assert_eq!(buyer, bob!());
+                assert_eq!(price, 100);
+            } else {
+                panic!("Expected to find Sold event");
+            };
+        }
+
+        #[ink::test]
+        fn buy_with_wrong_price() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.list(seed!(0), 100).unwrap();
+
+            use_account!(bob!(), 99);
+
+            assert_eq!(nft.buy(seed!(0)), Err(Error::InvalidPrice));
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+        }
+
+        #[ink::test]
+        fn buy_unlisted_token() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!(), 100);
+
+            assert_eq!(nft.buy(seed!(0)), Err(Error::NotListed));
+        }
+
+        #[ink::test]
+        fn transfer_invalidates_listing() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.list(seed!(0), 100).unwrap();
+
+            nft.transfer(charlie!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.listing_of(seed!(0)), None);
+
+            if let Event::Delisted(Delisted { seed }) = get_event(recorded_events().count() - 2) {
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find Delisted event");
+            };
+
+            use_account!(bob!(), 100);
+
+            assert_eq!(nft.buy(seed!(0)), Err(Error::NotListed));
+        }
+
+        #[ink::test]
+        fn burn_invalidates_listing() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.list(seed!(0), 100).unwrap();
+
+            nft.burn(seed!(0)).unwrap();
+
+            assert_eq!(nft.listing_of(seed!(0)), None);
+
+            if let Event::Delisted(Delisted { seed }) = get_event(recorded_events().count() - 2) {
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find Delisted event");
+            };
+        }
+
+        #[ink::test]
+        fn create_auction() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), contract_id());
+            assert_eq!(
+                nft.auction_of(seed!(0)),
+                Some(Auction {
+                    seller: alice!(),
+                    reserve: 100,
+                    end_block: 50,
+                    highest_bid: None,
+                })
+            );
+
+            if let Event::AuctionCreated(AuctionCreated {
+                seed,
+                seller,
+                reserve,
+                end_block,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(seller, alice!());
+                assert_eq!(reserve, 100);
+                assert_eq!(end_block, 50);
+            } else {
+                panic!("Expected to find AuctionCreated event");
+            };
+        }
+
+        #[ink::test]
+        fn create_auction_in_the_past() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            advance_blocks!(10);
+
+            assert_eq!(
+                nft.create_auction(seed!(0), 100, 10),
+                Err(Error::InvalidDeadline)
+            );
+        }
+
+        #[ink::test]
+        fn bid_refunds_previous_bidder() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+            set_balance(contract_id(), 1000);
+
+            use_account!(bob!(), 100);
+            nft.bid(seed!(0)).unwrap();
+            let bob_balance = account_balance(bob!());
+
+            use_account!(charlie!(), 100);
+            assert_eq!(nft.bid(seed!(0)), Err(Error::BidTooLow));
+
+            use_account!(charlie!(), 150);
+            nft.bid(seed!(0)).unwrap();
+
+            assert_eq!(account_balance(bob!()), bob_balance + 100);
+            assert_eq!(
+                nft.auction_of(seed!(0)).unwrap().highest_bid,
+                Some((charlie!(), 150))
+            );
+        }
+
+        #[ink::test]
+        fn bid_below_reserve() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+
+            use_account!(bob!(), 99);
+
+            assert_eq!(nft.bid(seed!(0)), Err(Error::BidTooLow));
+        }
+
+        #[ink::test]
+        fn late_bid_extends_auction() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+            advance_blocks!(45);
+
+            use_account!(bob!(), 100);
+            nft.bid(seed!(0)).unwrap();
+
+            assert_eq!(
+                nft.auction_of(seed!(0)).unwrap().end_block,
+                45 + ANTI_SNIPING_WINDOW
+            );
+
+            if let Event::AuctionExtended(AuctionExtended { seed, end_block }) = last_event() {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(end_block, 45 + ANTI_SNIPING_WINDOW);
+            } else {
+                panic!("Expected to find AuctionExtended event");
+            };
+        }
+
+        #[ink::test]
+        fn bid_after_end() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+            advance_blocks!(51);
+
+            use_account!(bob!(), 100);
+
+            assert_eq!(nft.bid(seed!(0)), Err(Error::AuctionEnded));
+        }
+
+        #[ink::test]
+        fn settle() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+            set_balance(contract_id(), 1000);
+            let seller_balance = account_balance(alice!());
+
+            use_account!(bob!(), 120);
+            nft.bid(seed!(0)).unwrap();
+
+            assert_eq!(nft.settle(seed!(0)), Err(Error::AuctionNotEnded));
+
+            advance_blocks!(51);
+            nft.settle(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.auction_of(seed!(0)), None);
+            assert_eq!(account_balance(alice!()), seller_balance + 120);
+
+            if let Event::AuctionSettled(AuctionSettled {
+                seed,
+                winner,
+                amount,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(winner, Some(bob!()));
+                assert_eq!(amount, 120);
+            } else {
+                panic!("Expected to find AuctionSettled event");
+            };
+        }
+
+        #[ink::test]
+        fn settle_without_bids() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+            advance_blocks!(51);
+
+            nft.settle(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+        }
+
+        #[ink::test]
+        fn cancel_auction() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+
+            use_account!(bob!());
+            assert_eq!(nft.cancel_auction(seed!(0)), Err(Error::NotAllowed));
+
+            use_account!(alice!());
+            nft.cancel_auction(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            assert_eq!(nft.auction_of(seed!(0)), None);
+        }
+
+        #[ink::test]
+        fn cancel_auction_with_bids() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.create_auction(seed!(0), 100, 50).unwrap();
+
+            use_account!(bob!(), 100);
+            nft.bid(seed!(0)).unwrap();
+
+            use_account!(alice!());
+
+            assert_eq!(nft.cancel_auction(seed!(0)), Err(Error::HasBids));
+        }
+
+        #[ink::test]
+        fn propose_trade() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!());
+            let id = nft
+                .propose_trade(vec![seed!(0)], vec![seed!(1)], bob!(), 10)
+                .unwrap();
+
+            assert_eq!(
+                nft.trade(id),
+                Some(Trade {
+                    proposer: alice!(),
+                    counterparty: bob!(),
+                    offered: vec![seed!(0)],
+                    requested: vec![seed!(1)],
+                    expiry: 10,
+                })
+            );
+
+            if let Event::TradeProposed(TradeProposed {
+                id: proposed,
+                proposer,
+                counterparty,
+                ..
+            }) = last_event()
+            {
+                assert_eq!(proposed, id);
+                assert_eq!(proposer, alice!());
+                assert_eq!(counterparty, bob!());
+            } else {
+                panic!("Expected to find TradeProposed event");
+            };
+        }
+
+        #[ink::test]
+        fn propose_trade_for_tokens_not_owned() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(
+                nft.propose_trade(vec![seed!(0)], vec![seed!(1)], bob!(), 10),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(
+                nft.propose_trade(vec![seed!(0)], vec![seed!(0)], bob!(), 10),
+                Err(Error::InvalidTrade)
+            );
+        }
+
+        #[ink::test]
+        fn accept_trade() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(2)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!());
+            let id = nft
+                .propose_trade(vec![seed!(0), seed!(2)], vec![seed!(1)], bob!(), 10)
+                .unwrap();
+
+            assert_eq!(nft.accept_trade(id), Err(Error::NotAllowed));
+
+            use_account!(bob!());
+            nft.accept_trade(id).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.owner_of(seed!(2)), bob!());
+            assert_eq!(nft.owner_of(seed!(1)), alice!());
+            assert_eq!(nft.trade(id), None);
+        }
+
+        #[ink::test]
+        fn accept_trade_after_ownership_changed() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!());
+            let id = nft
+                .propose_trade(vec![seed!(0)], vec![seed!(1)], bob!(), 10)
+                .unwrap();
+            nft.transfer(charlie!(), seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.accept_trade(id), Err(Error::NotOwner));
+            assert_eq!(nft.owner_of(seed!(1)), bob!());
+        }
+
+        #[ink::test]
+        fn accept_expired_trade() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!());
+            let id = nft
+                .propose_trade(vec![seed!(0)], vec![seed!(1)], bob!(), 10)
+                .unwrap();
+            advance_blocks!(11);
+
+            use_account!(bob!());
+
+            assert_eq!(nft.accept_trade(id), Err(Error::TradeExpired));
+        }
+
+        #[ink::test]
+        fn cancel_trade() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!());
+            let id = nft
+                .propose_trade(vec![seed!(0)], vec![seed!(1)], bob!(), 10)
+                .unwrap();
+
+            use_account!(charlie!());
+            assert_eq!(nft.cancel_trade(id), Err(Error::NotAllowed));
+
+            use_account!(bob!());
+            nft.cancel_trade(id).unwrap();
+
+            assert_eq!(nft.trade(id), None);
+            assert_eq!(nft.accept_trade(id), Err(Error::TradeNotFound));
+        }
+
+        #[ink::test]
+        fn royalty_info_rounds_down() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_royalty(250).unwrap();
+
+            assert_eq!(nft.royalty_info(seed!(0), 1000), (alice!(), 25));
+            assert_eq!(nft.royalty_info(seed!(0), 399), (alice!(), 9));
+            assert_eq!(nft.royalty_info(seed!(0), 39), (alice!(), 0));
+            assert_eq!(
+                nft.royalty_info(seed!(0), Balance::MAX),
+                (alice!(), Balance::MAX / 40)
+            );
+        }
+
+        #[ink::test]
+        fn species_royalty_overrides_default() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.set_royalty(250).unwrap();
+
+            // seed 0 is a Tentacool
+            nft.set_species_royalty(72, charlie!(), 1000).unwrap();
+
+            assert_eq!(nft.royalty_info(seed!(0), 1000), (charlie!(), 100));
+            assert_eq!(nft.royalty_info(seed!(1), 1000), (alice!(), 25));
+
+            nft.clear_species_royalty(72).unwrap();
+
+            assert_eq!(nft.royalty_info(seed!(0), 1000), (alice!(), 25));
+        }
+
+        #[ink::test]
+        fn royalty_cap() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_royalty(1000).unwrap();
+
+            assert_eq!(nft.set_royalty(1001), Err(Error::RoyaltyTooHigh));
+            assert_eq!(
+                nft.set_royalty_cap(MAX_BASIS_POINTS + 1),
+                Err(Error::RoyaltyTooHigh)
+            );
+
+            nft.set_royalty_cap(500).unwrap();
+
+            assert_eq!(nft.royalty_info(seed!(0), 1000), (alice!(), 50));
+        }
+
+        #[ink::test]
+        fn royalty_settings_are_admin_only() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.set_royalty(100), Err(Error::NotAllowed));
+            assert_eq!(nft.set_royalty_cap(100), Err(Error::NotAllowed));
+            assert_eq!(nft.set_royalty_receiver(bob!()), Err(Error::NotAllowed));
+            assert_eq!(
+                nft.set_species_royalty(1, bob!(), 100),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft.set_admin(bob!()), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn buy_pays_royalty() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_royalty(250).unwrap();
+            nft.set_royalty_receiver(charlie!()).unwrap();
+            nft.list(seed!(0), 1000).unwrap();
+            set_balance(contract_id(), 1000);
+            let seller_balance = account_balance(alice!());
+            let receiver_balance = account_balance(charlie!());
+
+            use_account!(bob!(), 1000);
+            nft.buy(seed!(0)).unwrap();
+
+            assert_eq!(account_balance(alice!()), seller_balance + 975);
+            assert_eq!(account_balance(charlie!()), receiver_balance + 25);
+
+            if let Event::RoyaltyPaid(RoyaltyPaid {
+                seed,
+                receiver,
+                amount,
+            }) = get_event(recorded_events().count() - 2)
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(receiver, charlie!());
+                assert_eq!(amount, 25);
+            } else {
+                panic!("Expected to find RoyaltyPaid event");
+            };
+        }
+
+        #[ink::test]
+        fn mint_batch() {
+            let mut nft = PokeNFT::new();
+
+            nft.mint_batch(vec![seed!(0), seed!(1), seed!(2)]).unwrap();
+
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(0), seed!(1), seed!(2)]);
+            assert_eq!(recorded_events().count(), 3);
+
+            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
+                assert_eq!(from, None);
+                assert_eq!(to, Some(alice!()));
+                assert_eq!(seed, seed!(2));
+            } else {
+                panic!("Expected to find Transfer event");
+            };
+        }
+
+        #[ink::test]
+        fn mint_batch_is_all_or_nothing() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(
+                nft.mint_batch(vec![seed!(0), seed!(1)]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::TokenAlreadyExists
+                })
+            );
+            assert_eq!(
+                nft.mint_batch(vec![seed!(0), seed!(2), seed!(0)]),
+                Err(BatchError {
+                    index: Some(2),
+                    error: Error::TokenAlreadyExists
+                })
+            );
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(1)]);
+        }
+
+        #[ink::test]
+        fn batch_too_large() {
+            let mut nft = PokeNFT::new();
+            nft.set_max_batch_size(2).unwrap();
+
+            assert_eq!(
+                nft.mint_batch(vec![seed!(0), seed!(1), seed!(2)]),
+                Err(BatchError {
+                    index: Some(2),
+                    error: Error::BatchTooLarge
+                })
+            );
+            assert_eq!(nft.balance_of(alice!()), 0);
+        }
+
+        #[ink::test]
+        fn set_max_batch_size_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.set_max_batch_size(1000), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn transfer_batch() {
+            let mut nft = PokeNFT::new();
+            nft.mint_batch(vec![seed!(0), seed!(1), seed!(2)]).unwrap();
+
+            nft.transfer_batch(bob!(), vec![seed!(0), seed!(2)])
+                .unwrap();
+
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(1)]);
+            assert_eq!(nft.tokens_of(bob!()), vec![seed!(0), seed!(2)]);
+        }
+
+        #[ink::test]
+        fn transfer_batch_is_all_or_nothing() {
+            let mut nft = PokeNFT::new();
+            nft.mint_batch(vec![seed!(0), seed!(1)]).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(2)).unwrap();
+
+            use_account!(alice!());
+
+            assert_eq!(
+                nft.transfer_batch(charlie!(), vec![seed!(0), seed!(1), seed!(2)]),
+                Err(BatchError {
+                    index: Some(2),
+                    error: Error::NotAllowed
+                })
+            );
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(0), seed!(1)]);
+            assert_eq!(nft.balance_of(charlie!()), 0);
+        }
+
+        #[ink::test]
+        fn transfer_batch_with_duplicates() {
+            let mut nft = PokeNFT::new();
+            nft.mint_batch(vec![seed!(0), seed!(1)]).unwrap();
+
+            assert_eq!(
+                nft.transfer_batch(bob!(), vec![seed!(0), seed!(1), seed!(0)]),
+                Err(BatchError {
+                    index: Some(2),
+                    error: Error::DuplicateSeed
+                })
+            );
+            assert_eq!(
+                nft.transfer_batch(alice!(), vec![seed!(1), seed!(1)]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::DuplicateSeed
+                })
+            );
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn approve_batch() {
+            let mut nft = PokeNFT::new();
+            nft.mint_batch(vec![seed!(0), seed!(1)]).unwrap();
+
+            assert_eq!(
+                nft.approve_batch(bob!(), vec![seed!(0), seed!(3)]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::TokenNotFound
+                })
+            );
+            assert_eq!(nft.get_approved(seed!(0)), None);
+
+            nft.approve_batch(bob!(), vec![seed!(0), seed!(1)]).unwrap();
+
+            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+            assert_eq!(nft.get_approved(seed!(1)), Some(bob!()));
+        }
+
+        #[ink::test]
+        fn mint_to() {
+            let mut nft = PokeNFT::new();
+
+            nft.mint_to(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+
+            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
+                assert_eq!(from, None);
+                assert_eq!(to, Some(bob!()));
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find Transfer event");
+            };
+        }
+
+        #[ink::test]
+        fn mint_to_while_not_minter() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.mint_to(bob!(), seed!(0)), Err(Error::NotAllowed));
+            assert_eq!(
+                nft.airdrop(vec![(bob!(), seed!(0))]),
+                Err(BatchError {
+                    index: None,
+                    error: Error::NotAllowed
+                })
+            );
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn granted_minter_can_mint_to() {
+            let mut nft = PokeNFT::new();
+            nft.set_minter(bob!(), true).unwrap();
+
+            use_account!(bob!());
+            nft.mint_to(charlie!(), seed!(0)).unwrap();
+
+            use_account!(alice!());
+            nft.set_minter(bob!(), false).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.owner_of(seed!(0)), charlie!());
+            assert_eq!(nft.mint_to(charlie!(), seed!(1)), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn airdrop() {
+            let mut nft = PokeNFT::new();
+
+            nft.airdrop(vec![(bob!(), seed!(0)), (charlie!(), seed!(1))])
+                .unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.owner_of(seed!(1)), charlie!());
+            assert_eq!(recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn mint_to_while_minting_closed() {
+            let mut nft = PokeNFT::new();
+            nft.set_mint_phase(MintPhase::Closed).unwrap();
+
+            nft.mint_to(bob!(), seed!(0)).unwrap();
+            nft.airdrop(vec![(charlie!(), seed!(1))]).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.owner_of(seed!(1)), charlie!());
+        }
+
+        #[ink::test]
+        fn mint_to_is_rate_limited() {
+            let mut nft = PokeNFT::new();
+            nft.set_mint_limits(MintLimits {
+                per_account: Some(1),
+                per_block: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+
+            // limits are counted against the recipient, not the minter
+            nft.mint_to(bob!(), seed!(0)).unwrap();
+            assert_eq!(nft.mint_to(bob!(), seed!(1)), Err(Error::RateLimited));
+            assert_eq!(nft.mints_remaining(alice!()), Some(1));
+
+            // one mint left in this block
+            assert_eq!(
+                nft.airdrop(vec![(charlie!(), seed!(1)), (alice!(), seed!(2))]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::RateLimited
+                })
+            );
+
+            nft.airdrop(vec![(charlie!(), seed!(1))]).unwrap();
+
+            assert_eq!(nft.balance_of(charlie!()), 1);
+            assert_eq!(nft.mints_remaining(alice!()), Some(0));
+
+            advance_blocks!(1);
+
+            assert_eq!(
+                nft.airdrop(vec![(alice!(), seed!(2)), (alice!(), seed!(3))]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::RateLimited
+                })
+            );
+
+            nft.mint_to(alice!(), seed!(2)).unwrap();
+        }
+
+        #[ink::test]
+        fn airdrop_is_all_or_nothing() {
+            let mut nft = PokeNFT::new();
+
+            assert_eq!(
+                nft.airdrop(vec![(bob!(), seed!(0)), (zero_account!(), seed!(1))]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::InvalidAddress
+                })
+            );
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn redeem_voucher() {
+            let mut nft = PokeNFT::new();
+            nft.set_voucher_signer(Some(voucher_signer())).unwrap();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: None,
+                expires_at: 10,
+                nonce: 1,
+            };
+
+            use_account!(bob!());
+            nft.redeem_voucher(voucher, sign_voucher(&voucher)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.pokemon_of(seed!(0)), 72);
+
+            if let Event::VoucherRedeemed(VoucherRedeemed {
+                nonce,
+                recipient,
+                seed,
+            }) = last_event()
+            {
+                assert_eq!(nonce, 1);
+                assert_eq!(recipient, bob!());
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find VoucherRedeemed event");
+            };
+        }
+
+        #[ink::test]
+        fn redeem_voucher_with_species() {
+            let mut nft = PokeNFT::new();
+            nft.set_voucher_signer(Some(voucher_signer())).unwrap();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: Some(25),
+                expires_at: 10,
+                nonce: 1,
+            };
+
+            nft.redeem_voucher(voucher, sign_voucher(&voucher)).unwrap();
+
+            assert_eq!(nft.pokemon_of(seed!(0)), 25);
+        }
+
+        #[ink::test]
+        fn redeem_voucher_twice() {
+            let mut nft = PokeNFT::new();
+            nft.set_voucher_signer(Some(voucher_signer())).unwrap();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: None,
+                expires_at: 10,
+                nonce: 1,
+            };
+            let replay = Voucher {
+                seed: seed!(1),
+                ..voucher
+            };
+
+            nft.redeem_voucher(voucher, sign_voucher(&voucher)).unwrap();
+
+            assert_eq!(
+                nft.redeem_voucher(voucher, sign_voucher(&voucher)),
+                Err(Error::VoucherAlreadyRedeemed)
+            );
+            assert_eq!(
+                nft.redeem_voucher(replay, sign_voucher(&replay)),
+                Err(Error::VoucherAlreadyRedeemed)
+            );
+        }
+
+        #[ink::test]
+        fn redeem_expired_voucher() {
+            let mut nft = PokeNFT::new();
+            nft.set_voucher_signer(Some(voucher_signer())).unwrap();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: None,
+                expires_at: 10,
+                nonce: 1,
+            };
+            advance_blocks!(11);
+
+            assert_eq!(
+                nft.redeem_voucher(voucher, sign_voucher(&voucher)),
+                Err(Error::VoucherExpired)
+            );
+        }
+
+        #[ink::test]
+        fn redeem_tampered_voucher() {
+            let mut nft = PokeNFT::new();
+            nft.set_voucher_signer(Some(voucher_signer())).unwrap();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: None,
+                expires_at: 10,
+                nonce: 1,
+            };
+            let tampered = Voucher {
+                recipient: charlie!(),
+                ..voucher
+            };
+
+            assert_eq!(
+                nft.redeem_voucher(tampered, sign_voucher(&voucher)),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(
+                nft.redeem_voucher(voucher, vec![0; 64]),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(nft.balance_of(charlie!()), 0);
+        }
+
+        #[ink::test]
+        fn redeem_voucher_for_another_contract() {
+            let mut nft = PokeNFT::new();
+            nft.set_voucher_signer(Some(voucher_signer())).unwrap();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: None,
+                expires_at: 10,
+                nonce: 1,
+            };
+
+            assert_eq!(
+                nft.redeem_voucher(voucher, sign_voucher_for(charlie!(), &voucher)),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn redeem_voucher_without_signer() {
+            let mut nft = PokeNFT::new();
+            let voucher = Voucher {
+                recipient: bob!(),
+                seed: seed!(0),
+                pokemon: None,
+                expires_at: 10,
+                nonce: 1,
+            };
+
+            assert_eq!(
+                nft.redeem_voucher(voucher, sign_voucher(&voucher)),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn mint_limit_per_account() {
+            let mut nft = PokeNFT::new();
+            nft.set_mint_limits(MintLimits {
+                per_account: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+
+            assert_eq!(nft.mints_remaining(alice!()), Some(2));
+
+            nft.mint(seed!(0)).unwrap();
+            advance_blocks!(100);
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.mints_remaining(alice!()), Some(0));
+            assert_eq!(nft.mint(seed!(2)), Err(Error::RateLimited));
+            assert_eq!(nft.mints_remaining(bob!()), Some(2));
+        }
+
+        #[ink::test]
+        fn mint_limit_per_window() {
+            let mut nft = PokeNFT::new();
+            nft.set_mint_limits(MintLimits {
+                per_window: Some((2, 10)),
+                ..Default::default()
+            })
+            .unwrap();
+
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.mint(seed!(2)), Err(Error::RateLimited));
+
+            advance_blocks!(9);
+
+            assert_eq!(nft.mint(seed!(2)), Err(Error::RateLimited));
+
+            advance_blocks!(1);
+
+            assert_eq!(nft.mints_remaining(alice!()), Some(2));
+            nft.mint(seed!(2)).unwrap();
+        }
+
+        #[ink::test]
+        fn mint_limit_per_block() {
+            let mut nft = PokeNFT::new();
+            nft.set_mint_limits(MintLimits {
+                per_block: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            assert_eq!(nft.mints_remaining(bob!()), Some(0));
+            assert_eq!(nft.mint(seed!(1)), Err(Error::RateLimited));
+
+            advance_blocks!(1);
+            nft.mint(seed!(1)).unwrap();
+        }
+
+        #[ink::test]
+        fn mint_batch_respects_limits() {
+            let mut nft = PokeNFT::new();
+            nft.set_mint_limits(MintLimits {
+                per_account: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+
+            assert_eq!(
+                nft.mint_batch(vec![seed!(0), seed!(1), seed!(2)]),
+                Err(BatchError {
+                    index: Some(2),
+                    error: Error::RateLimited
+                })
+            );
+
+            nft.mint_batch(vec![seed!(0), seed!(1)]).unwrap();
+
+            assert_eq!(nft.mints_remaining(alice!()), Some(0));
+        }
+
+        #[ink::test]
+        fn unlimited_mints() {
+            let nft = PokeNFT::new();
+
+            assert_eq!(nft.mints_remaining(alice!()), None);
+        }
+
+        #[ink::test]
+        fn set_mint_limits_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(
+                nft.set_mint_limits(MintLimits::default()),
+                Err(Error::NotAllowed)
+            );
+        }
+
+        #[test]
+        fn merkle_proofs() {
+            let leaves: Vec<merkle::Node> = (0..5u8)
+                .map(|n| merkle::leaf(&AccountId::from([n; 32]), 1))
+                .collect();
+            let (root, proofs) = merkle_tree(&leaves);
+
+            for (leaf, proof) in leaves.iter().zip(proofs.iter()) {
+                assert!(merkle::verify(root, *leaf, proof));
+            }
+
+            // a proof is only valid for its own leaf
+            assert!(!merkle::verify(root, leaves[0], &proofs[1]));
+            assert!(!merkle::verify(
+                root,
+                merkle::leaf(&AccountId::from([0; 32]), 2),
+                &proofs[0]
+            ));
+        }
+
+        // pins the leaf encoding and tree layout, so off-chain tooling has something to check
+        // against. leaves are blake2b-256 of the SCALE encoded `(account, quota)`
+        #[test]
+        fn merkle_test_vectors() {
+            let leaves = [
+                merkle::leaf(&AccountId::from([1; 32]), 1),
+                merkle::leaf(&AccountId::from([2; 32]), 2),
+                merkle::leaf(&AccountId::from([3; 32]), 1),
+            ];
+
+            assert_eq!(
+                leaves,
+                [
+                    node("b88dafc9c1213c2ecfb7bf9979c8ae312effd2cb5d495d950552bfae733fa805"),
+                    node("d17d35373f4cf87ff9800c8305e7a68c8c2d97c68b06af7cf0e0d1d0ffa23454"),
+                    node("1db098c6f46e4f15c9034f090efbc0361fed9eb7d3d5073780c9401a42bdfa55"),
+                ]
+            );
+
+            let (root, proofs) = merkle_tree(&leaves);
+
+            assert_eq!(
+                root,
+                node("9117d5911396ce0f299948965786ce23af1efc9b55abea97c2e93dddc72072ef")
+            );
+            assert_eq!(
+                proofs[2],
+                vec![node(
+                    "a7c071b7d91fa92a8fe29efe77d448d4c9bb47d1932ace4473c3b9840d102e72"
+                )]
+            );
+            assert!(merkle::verify(root, leaves[2], &proofs[2]));
+        }
+
+        #[ink::test]
+        fn presale_mint() {
+            let mut nft = PokeNFT::new();
+            let (root, proofs) = merkle_tree(&[
+                merkle::leaf(&alice!(), 2),
+                merkle::leaf(&bob!(), 1),
+                merkle::leaf(&charlie!(), 1),
+            ]);
+            nft.set_presale_root(Some(root)).unwrap();
+            nft.set_mint_phase(MintPhase::Presale).unwrap();
+
+            assert_eq!(nft.mint(seed!(0)), Err(Error::MintingClosed));
+
+            nft.presale_mint(seed!(0), 2, proofs[0].clone()).unwrap();
+            nft.presale_mint(seed!(1), 2, proofs[0].clone()).unwrap();
+
+            assert_eq!(nft.presale_minted(alice!()), 2);
+            assert_eq!(
+                nft.presale_mint(seed!(2), 2, proofs[0].clone()),
+                Err(Error::QuotaExceeded)
+            );
+
+            use_account!(bob!());
+            nft.presale_mint(seed!(2), 1, proofs[1].clone()).unwrap();
+
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(0), seed!(1)]);
+            assert_eq!(nft.tokens_of(bob!()), vec![seed!(2)]);
+        }
+
+        #[ink::test]
+        fn presale_mint_is_rate_limited() {
+            let mut nft = PokeNFT::new();
+            let (root, proofs) = merkle_tree(&[merkle::leaf(&alice!(), 2)]);
+            nft.set_presale_root(Some(root)).unwrap();
+            nft.set_mint_phase(MintPhase::Presale).unwrap();
+            nft.set_mint_limits(MintLimits {
+                per_account: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+
+            nft.presale_mint(seed!(0), 2, proofs[0].clone()).unwrap();
+
+            assert_eq!(nft.mints_remaining(alice!()), Some(0));
+            assert_eq!(
+                nft.presale_mint(seed!(1), 2, proofs[0].clone()),
+                Err(Error::RateLimited)
+            );
+        }
+
+        #[ink::test]
+        fn presale_mint_with_invalid_proof() {
+            let mut nft = PokeNFT::new();
+            let (root, proofs) =
+                merkle_tree(&[merkle::leaf(&alice!(), 1), merkle::leaf(&bob!(), 1)]);
+            nft.set_presale_root(Some(root)).unwrap();
+            nft.set_mint_phase(MintPhase::Presale).unwrap();
+
+            // claiming a bigger quota than allowlisted
+            assert_eq!(
+                nft.presale_mint(seed!(0), 5, proofs[0].clone()),
+                Err(Error::InvalidProof)
+            );
+
+            use_account!(charlie!());
+
+            assert_eq!(
+                nft.presale_mint(seed!(0), 1, proofs[0].clone()),
+                Err(Error::InvalidProof)
+            );
+        }
+
+        #[ink::test]
+        fn presale_mint_outside_presale() {
+            let mut nft = PokeNFT::new();
+            let (root, proofs) = merkle_tree(&[merkle::leaf(&alice!(), 1)]);
+            nft.set_presale_root(Some(root)).unwrap();
+
+            assert_eq!(
+                nft.presale_mint(seed!(0), 1, proofs[0].clone()),
+                Err(Error::MintingClosed)
+            );
+
+            nft.set_mint_phase(MintPhase::Closed).unwrap();
+
+            assert_eq!(nft.mint(seed!(0)), Err(Error::MintingClosed));
+            assert_eq!(
+                nft.mint_batch(vec![seed!(0)]),
+                Err(BatchError {
+                    index: None,
+                    error: Error::MintingClosed
+                })
+            );
+            assert_eq!(
+                nft.presale_mint(seed!(0), 1, proofs[0].clone()),
+                Err(Error::MintingClosed)
+            );
+        }
+
+        #[ink::test]
+        fn set_mint_phase_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(
+                nft.set_mint_phase(MintPhase::Closed),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft.set_presale_root(None), Err(Error::NotAllowed));
+        }
+
+        #[test]
+        fn rare_boost() {
+            let rares = |boost: u32| {
+                (0..=255)
+                    .filter(|n| {
+                        let pokemon = rng::sample_weighted(seed!(*n), |population| {
+                            if population < rng::RARE_POPULATION {
+                                population * boost
+                            } else {
+                                population
+                            }
+                        })
+                        .unwrap();
+
+                        rng::is_rare(pokemon)
+                    })
+                    .count()
+            };
+
+            assert!(rares(PackType::Legendary.rare_boost()) > rares(PackType::Basic.rare_boost()));
+        }
+
+        #[ink::test]
+        fn open_pack() {
+            let mut nft = PokeNFT::new();
+            nft.set_pack_price(PackType::Great, Some(100)).unwrap();
+            set_balance(contract_id(), 100);
+
+            use_account!(bob!(), 100);
+            let seeds = nft.open_pack(PackType::Great).unwrap();
+
+            assert_eq!(seeds.len(), 5);
+            assert_eq!(nft.tokens_of(bob!()), seeds);
+
+            if let Event::PackOpened(PackOpened {
+                owner,
+                pack_type,
+                seeds: opened,
+                pokemon,
+            }) = last_event()
+            {
+                assert_eq!(owner, bob!());
+                assert_eq!(pack_type, PackType::Great);
+                assert_eq!(opened, seeds);
+                assert_eq!(
+                    pokemon,
+                    seeds
+                        .iter()
+                        .map(|seed| nft.pokemon_of(*seed))
+                        .collect::<Vec<_>>()
+                );
+            } else {
+                panic!("Expected to find PackOpened event");
+            };
+        }
+
+        #[ink::test]
+        fn open_pack_twice() {
+            let mut nft = PokeNFT::new();
+            nft.set_pack_price(PackType::Basic, Some(100)).unwrap();
+            set_balance(contract_id(), 200);
+
+            use_account!(bob!(), 100);
+            let first = nft.open_pack(PackType::Basic).unwrap();
+            let second = nft.open_pack(PackType::Basic).unwrap();
+
+            assert_ne!(first, second);
+            assert_eq!(nft.balance_of(bob!()), 6);
+        }
+
+        #[ink::test]
+        fn open_pack_is_rate_limited() {
+            let mut nft = PokeNFT::new();
+            nft.set_pack_price(PackType::Basic, Some(100)).unwrap();
+            nft.set_mint_limits(MintLimits {
+                per_account: Some(4),
+                ..Default::default()
+            })
+            .unwrap();
+            set_balance(contract_id(), 100);
+
+            use_account!(bob!(), 100);
+            nft.open_pack(PackType::Basic).unwrap();
+
+            assert_eq!(nft.mints_remaining(bob!()), Some(1));
+            assert_eq!(nft.open_pack(PackType::Basic), Err(Error::RateLimited));
+            assert_eq!(nft.balance_of(bob!()), 3);
+        }
+
+        #[ink::test]
+        fn pity_applies_within_pack() {
+            let mut nft = PokeNFT::new();
+            nft.set_pack_price(PackType::Basic, Some(100)).unwrap();
+            // every pull is due for pity
+            nft.set_pity_threshold(1).unwrap();
+            set_balance(contract_id(), 100);
+
+            use_account!(bob!(), 100);
+            let seeds = nft.open_pack(PackType::Basic).unwrap();
+
+            assert!(seeds.iter().all(|seed| rng::is_rare(nft.pokemon_of(*seed))));
+            assert_eq!(nft.pity_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn open_pack_with_wrong_price() {
+            let mut nft = PokeNFT::new();
+            nft.set_pack_price(PackType::Basic, Some(100)).unwrap();
+
+            use_account!(bob!(), 99);
+
+            assert_eq!(nft.open_pack(PackType::Basic), Err(Error::InvalidPrice));
+            assert_eq!(
+                nft.open_pack(PackType::Legendary),
+                Err(Error::PackUnavailable)
+            );
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn pity_guarantees_rare_pull() {
+            let mut nft = PokeNFT::new();
+            nft.set_pity_threshold(3).unwrap();
+
+            // Tentacool and Rattata
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.pity_of(alice!()), 2);
+            assert_eq!(rng::sample(seed!(2)), Ok(69));
+
+            nft.mint(seed!(2)).unwrap();
+
+            // Aerodactyl instead of Bellsprout
+            assert_eq!(nft.pokemon_of(seed!(2)), 142);
+            assert_eq!(nft.pity_of(alice!()), 0);
+        }
+
+        #[ink::test]
+        fn pity_is_per_account() {
+            let mut nft = PokeNFT::new();
+            nft.set_pity_threshold(2).unwrap();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            assert_eq!(nft.pokemon_of(seed!(1)), 19);
+            assert_eq!(nft.pity_of(alice!()), 1);
+            assert_eq!(nft.pity_of(bob!()), 1);
+        }
+
+        #[ink::test]
+        fn pity_applies_within_batch() {
+            let mut nft = PokeNFT::new();
+            nft.set_pity_threshold(3).unwrap();
+
+            nft.mint_batch(vec![seed!(0), seed!(1), seed!(2), seed!(3)])
+                .unwrap();
+
+            assert_eq!(nft.pokemon_of(seed!(2)), 142);
+            assert_eq!(nft.pokemon_of(seed!(3)), 72);
+            assert_eq!(nft.pity_of(alice!()), 1);
+        }
+
+        #[ink::test]
+        fn disabled_pity() {
+            let mut nft = PokeNFT::new();
+            nft.set_pity_threshold(0).unwrap();
+
+            nft.mint_batch(vec![seed!(0), seed!(1), seed!(2)]).unwrap();
+
+            assert_eq!(nft.pokemon_of(seed!(2)), 69);
+        }
+
+        #[ink::test]
+        fn set_pity_threshold_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.set_pity_threshold(1), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn pokedex_survives_transfers() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.pokedex_of(alice!()), vec![19, 72]);
+            assert_eq!(nft.pokedex_of(bob!()), vec![72]);
+            assert_eq!(nft.pokedex_completion(alice!()), 2);
+            assert_eq!(nft.pokedex_completion(charlie!()), 0);
+        }
+
+        #[ink::test]
+        fn pokedex_counts_species_once() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(3)).unwrap();
+
+            assert_eq!(nft.pokedex_of(alice!()), vec![72]);
+            assert_eq!(nft.pokedex_completion(alice!()), 1);
+        }
+
+        #[ink::test]
+        fn all_starters_achievement() {
+            let mut nft = PokeNFT::new();
+            nft.add_token_to(&alice!(), seed!(1), 1).unwrap();
+            nft.add_token_to(&alice!(), seed!(2), 4).unwrap();
+
+            assert_eq!(recorded_events().count(), 0);
+
+            nft.add_token_to(&alice!(), seed!(3), 7).unwrap();
+
+            if let Event::Achievement(Achievement { account, milestone }) = last_event() {
+                assert_eq!(account, alice!());
+                assert_eq!(milestone, Milestone::AllStarters);
+            } else {
+                panic!("Expected to find Achievement event");
+            };
+
+            nft.add_token_to(&alice!(), seed!(4), 7).unwrap();
+
+            assert_eq!(recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn first_legendary_achievement() {
+            let mut nft = PokeNFT::new();
+            nft.add_token_to(&alice!(), seed!(1), 150).unwrap();
+            nft.add_token_to(&alice!(), seed!(2), 151).unwrap();
+
+            assert_eq!(recorded_events().count(), 1);
+
+            if let Event::Achievement(Achievement { account, milestone }) = last_event() {
+                assert_eq!(account, alice!());
+                assert_eq!(milestone, Milestone::FirstLegendary);
+            } else {
+                panic!("Expected to find Achievement event");
+            };
+        }
+
+        #[ink::test]
+        fn complete_pokedex_achievement() {
+            let mut nft = PokeNFT::new();
+
+            for pokemon in 1..=151u8 {
+                nft.add_token_to(&alice!(), seed!(pokemon), pokemon as PokemonId)
+                    .unwrap();
+            }
+
+            assert_eq!(nft.pokedex_completion(alice!()), 151);
+            assert_eq!(nft.pokedex_of(alice!()).len(), 151);
+
+            if let Event::Achievement(Achievement { milestone, .. }) = last_event() {
+                assert_eq!(milestone, Milestone::Complete);
+            } else {
+                panic!("Expected to find Achievement event");
+            };
+        }
+
+        #[ink::test]
+        fn award_badge() {
+            let mut nft = PokeNFT::new();
+            nft.register_badge(1, String::from("Boulder Badge"))
+                .unwrap();
+
+            nft.award_badge(bob!(), 1).unwrap();
+
+            assert_eq!(nft.badges_of(bob!()), vec![1]);
+            assert_eq!(nft.badge_name(1), Some(String::from("Boulder Badge")));
+            assert_eq!(nft.award_badge(bob!(), 1), Err(Error::BadgeAlreadyAwarded));
+            assert_eq!(nft.award_badge(bob!(), 2), Err(Error::BadgeNotFound));
+
+            if let Event::BadgeAwarded(BadgeAwarded { account, badge }) = last_event() {
+                assert_eq!(account, bob!());
+                assert_eq!(badge, 1);
+            } else {
+                panic!("Expected to find BadgeAwarded event");
+            };
+        }
+
+        #[ink::test]
+        fn badges_are_admin_only() {
+            let mut nft = PokeNFT::new();
+            nft.register_badge(1, String::from("Boulder Badge"))
+                .unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.award_badge(bob!(), 1), Err(Error::NotAllowed));
+            assert_eq!(
+                nft.register_badge(2, String::from("Cascade Badge")),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(
+                nft.set_milestone_badge(Milestone::Complete, Some(1)),
+                Err(Error::NotAllowed)
+            );
+        }
+
+        #[ink::test]
+        fn badges_are_soulbound() {
+            let mut nft = PokeNFT::new();
+            nft.register_badge(1, String::from("Boulder Badge"))
+                .unwrap();
+            nft.award_badge(alice!(), 1).unwrap();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(nft.transfer(bob!(), seed!(1)), Err(Error::TokenNotFound));
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.badges_of(alice!()), vec![1]);
+            assert_eq!(nft.badges_of(bob!()), Vec::<BadgeId>::new());
+            assert_eq!(nft.tokens_of(alice!()), Vec::<Seed>::new());
+        }
+
+        #[ink::test]
+        fn milestone_awards_badge() {
+            let mut nft = PokeNFT::new();
+            nft.register_badge(1, String::from("Starter Trainer"))
+                .unwrap();
+            nft.set_milestone_badge(Milestone::AllStarters, Some(1))
+                .unwrap();
+
+            nft.add_token_to(&alice!(), seed!(1), 1).unwrap();
+            nft.add_token_to(&alice!(), seed!(2), 4).unwrap();
+            nft.add_token_to(&alice!(), seed!(3), 7).unwrap();
+
+            assert_eq!(nft.badges_of(alice!()), vec![1]);
+            assert_eq!(
+                nft.set_milestone_badge(Milestone::Complete, Some(2)),
+                Err(Error::BadgeNotFound)
+            );
+        }
+
+        #[test]
+        fn battle_stats() {
+            assert_eq!(
+                battle::stats(1, [0; 4], 50),
+                Ok(Stats {
+                    hp: 105,
+                    attack: 54,
+                    defense: 54,
+                    speed: 50,
+                    special: 70,
+                })
+            );
+            assert_eq!(
+                battle::stats(113, [15; 4], 50).map(|stats| stats.hp),
+                Ok(325)
+            );
+            assert_eq!(battle::stats(0, [0; 4], 50), Err(Error::InvalidSeed));
+        }
+
+        #[test]
+        fn battle_effectiveness() {
+            use battle::Type::*;
+
+            assert_eq!(battle::effectiveness(Water, (Fire, None)), 200);
+            assert_eq!(battle::effectiveness(Fire, (Water, None)), 50);
+            assert_eq!(battle::effectiveness(Electric, (Water, Some(Flying))), 400);
+            assert_eq!(battle::effectiveness(Electric, (Ground, None)), 0);
+            // Gen-1 ghost moves famously don't affect psychic types
+            assert_eq!(battle::effectiveness(Ghost, (Psychic, None)), 0);
+            assert_eq!(battle::effectiveness(Normal, (Normal, None)), 100);
+        }
+
+        #[test]
+        fn battle_resolve() {
+            let combatant = |pokemon, n| battle::Combatant {
+                pokemon,
+                ivs: rng::ivs(seed!(n)),
+                level: 50,
+            };
+
+            for n in 0..5 {
+                let outcome =
+                    battle::resolve(combatant(150, n), combatant(129, n), seed!(n)).unwrap();
+                assert!(outcome.attacker_won);
+                assert_eq!(outcome.turns, 1);
+
+                let outcome =
+                    battle::resolve(combatant(129, n), combatant(150, n), seed!(n)).unwrap();
+                assert!(!outcome.attacker_won);
+            }
+
+            // Blastoise's water moves beat Charizard
+            let outcome = battle::resolve(combatant(9, 0), combatant(6, 0), seed!(0)).unwrap();
+            assert!(outcome.attacker_won);
+
+            // normal moves can't touch Gastly, which still hits back with poison
+            let outcome = battle::resolve(combatant(132, 0), combatant(92, 0), seed!(0)).unwrap();
+            assert!(!outcome.attacker_won);
+        }
+
+        #[ink::test]
+        fn battle_resolve_is_deterministic() {
+            let a = battle::Combatant {
+                pokemon: 6,
+                ivs: rng::ivs(seed!(1)),
+                level: 50,
+            };
+            let b = battle::Combatant {
+                pokemon: 3,
+                ivs: rng::ivs(seed!(2)),
+                level: 50,
+            };
+
+            assert_eq!(
+                battle::resolve(a, b, seed!(7)),
+                battle::resolve(a, b, seed!(7))
+            );
+            assert_ne!(
+                battle::resolve(a, b, seed!(7)).unwrap().log_hash,
+                battle::resolve(a, b, seed!(8)).unwrap().log_hash
+            );
+        }
+
+        #[ink::test]
+        fn battle() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!());
+            let outcome = nft.battle(seed!(0), seed!(1)).unwrap();
+
+            assert_eq!(outcome.attacker, seed!(0));
+            assert_eq!(outcome.defender, seed!(1));
+            assert!(outcome.winner == seed!(0) || outcome.winner == seed!(1));
+            assert!(outcome.turns >= 1 && outcome.turns <= battle::MAX_TURNS);
+
+            if let Event::BattleResult(BattleResult {
+                attacker,
+                defender,
+                winner,
+                turns,
+                log_hash,
+            }) = last_event()
+            {
+                assert_eq!(attacker, seed!(0));
+                assert_eq!(defender, seed!(1));
+                assert_eq!(winner, outcome.winner);
+                assert_eq!(turns, outcome.turns);
+                assert_eq!(log_hash, outcome.log_hash);
+            } else {
+                panic!("Expected to find BattleResult event");
+            }
+
+            // casual battles don't award experience
+            assert_eq!(nft.experience_of(outcome.winner), 0);
+
+            // battling doesn't change ownership
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            assert_eq!(nft.owner_of(seed!(1)), bob!());
+        }
+
+        #[ink::test]
+        fn battle_with_someone_elses_pokemon() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.battle(seed!(0), seed!(1)), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn battle_against_itself() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(nft.battle(seed!(0), seed!(0)), Err(Error::InvalidOpponent));
+            assert_eq!(nft.battle(seed!(0), seed!(1)), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn challenge() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!(), 50);
+            assert_eq!(
+                nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET)),
+                Ok(0)
+            );
+
+            assert_eq!(
+                nft.challenge_of(0),
+                Some(Challenge {
+                    challenger: alice!(),
+                    challenger_seed: seed!(0),
+                    opponent: bob!(),
+                    opponent_seed: seed!(1),
+                    stake: 50,
+                    expiry: CHALLENGE_TIMEOUT,
+                    commitment: commitment(CHALLENGE_SECRET),
+                    opponent_entropy: None,
+                    reveal_by: 0,
+                })
+            );
+            assert!(nft.is_locked(seed!(0)));
+            assert!(!nft.is_locked(seed!(1)));
+
+            if let Event::ChallengeCreated(ChallengeCreated {
+                id,
+                challenger,
+                opponent,
+                stake,
+                expiry,
+            }) = last_event()
+            {
+                assert_eq!(id, 0);
+                assert_eq!(challenger, alice!());
+                assert_eq!(opponent, bob!());
+                assert_eq!(stake, 50);
+                assert_eq!(expiry, CHALLENGE_TIMEOUT);
+            } else {
+                panic!("Expected to find ChallengeCreated event");
+            };
+        }
+
+        #[ink::test]
+        fn challenge_with_wrong_stake() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.transfer(bob!(), seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!(), 10);
+
+            assert_eq!(
+                nft.challenge(seed!(1), bob!(), seed!(0), 50, commitment(CHALLENGE_SECRET)),
+                Err(Error::InvalidPrice)
+            );
+        }
+
+        #[ink::test]
+        fn challenge_with_someone_elses_pokemon() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 0);
+
+            assert_eq!(
+                nft.challenge(seed!(1), bob!(), seed!(0), 0, commitment(CHALLENGE_SECRET)),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(
+                nft.challenge(
+                    seed!(0),
+                    charlie!(),
+                    seed!(1),
+                    0,
+                    commitment(CHALLENGE_SECRET)
+                ),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(
+                nft.challenge(seed!(0), bob!(), seed!(2), 0, commitment(CHALLENGE_SECRET)),
+                Err(Error::TokenNotFound)
+            );
+            assert_eq!(
+                nft.challenge(
+                    seed!(0),
+                    alice!(),
+                    seed!(0),
+                    0,
+                    commitment(CHALLENGE_SECRET)
+                ),
+                Err(Error::InvalidOpponent)
+            );
+        }
+
+        #[ink::test]
+        fn challenge_locks_token() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            assert_eq!(nft.transfer(charlie!(), seed!(0)), Err(Error::TokenLocked));
+            assert_eq!(
+                nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET)),
+                Err(Error::TokenLocked)
+            );
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+        }
+
+        #[ink::test]
+        fn accept_challenge() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+            set_balance(contract_id(), 100);
+            let alice_balance = account_balance(alice!());
+            let bob_balance = account_balance(bob!());
+
+            use_account!(bob!(), 50);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            let challenge = nft.challenge_of(0).unwrap();
+            assert_eq!(challenge.opponent_entropy, Some(OPPONENT_ENTROPY));
+            assert_eq!(challenge.reveal_by, CHALLENGE_REVEAL_TIMEOUT);
+            assert!(nft.is_locked(seed!(0)));
+            assert!(nft.is_locked(seed!(1)));
+            assert_eq!(
+                nft.accept_challenge(0, OPPONENT_ENTROPY),
+                Err(Error::ChallengeAccepted)
+            );
+
+            if let Event::ChallengeAccepted(ChallengeAccepted { id, reveal_by }) = last_event() {
+                assert_eq!(id, 0);
+                assert_eq!(reveal_by, CHALLENGE_REVEAL_TIMEOUT);
+            } else {
+                panic!("Expected to find ChallengeAccepted event");
+            };
+
+            use_account!(alice!(), 0);
+            let outcome = nft.reveal_challenge(0, CHALLENGE_SECRET).unwrap();
+
+            let (winner, winner_balance) = if outcome.winner == seed!(0) {
+                (alice!(), alice_balance)
+            } else {
+                (bob!(), bob_balance)
+            };
+
+            assert_eq!(account_balance(winner), winner_balance + 100);
+            assert_eq!(nft.challenge_of(0), None);
+            assert!(!nft.is_locked(seed!(0)));
+            assert!(!nft.is_locked(seed!(1)));
+
+            // level 1 pokemon yield enough experience to get to level 2
+            assert_eq!(nft.level_of(outcome.winner), 2);
+
+            if let Event::LevelUp(LevelUp { seed, level }) =
+                get_event(recorded_events().count() - 4)
+            {
+                assert_eq!(seed, outcome.winner);
+                assert_eq!(level, 2);
+            } else {
+                panic!("Expected to find LevelUp event");
+            };
+
+            if let Event::BattleResult(BattleResult {
+                attacker, defender, ..
+            }) = get_event(recorded_events().count() - 5)
+            {
+                assert_eq!(attacker, seed!(0));
+                assert_eq!(defender, seed!(1));
+            } else {
+                panic!("Expected to find BattleResult event");
+            };
+
+            if let Event::ChallengeSettled(ChallengeSettled {
+                id,
+                winner: settled_winner,
+                payout,
+            }) = last_event()
+            {
+                assert_eq!(id, 0);
+                assert_eq!(settled_winner, winner);
+                assert_eq!(payout, 100);
+            } else {
+                panic!("Expected to find ChallengeSettled event");
+            };
+        }
+
+        #[ink::test]
+        fn accept_challenge_while_not_opponent() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(charlie!(), 50);
+            assert_eq!(
+                nft.accept_challenge(0, OPPONENT_ENTROPY),
+                Err(Error::NotAllowed)
+            );
+
+            use_account!(bob!(), 10);
+            assert_eq!(
+                nft.accept_challenge(0, OPPONENT_ENTROPY),
+                Err(Error::InvalidPrice)
+            );
+            assert_eq!(
+                nft.accept_challenge(1, OPPONENT_ENTROPY),
+                Err(Error::ChallengeNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn accept_challenge_after_opponent_moved_token() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(bob!(), 0);
+            nft.transfer(charlie!(), seed!(1)).unwrap();
+
+            assert_eq!(
+                nft.accept_challenge(0, OPPONENT_ENTROPY),
+                Err(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn cancel_challenge() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+            set_balance(contract_id(), 50);
+            let alice_balance = account_balance(alice!());
+
+            use_account!(bob!());
+            assert_eq!(nft.cancel_challenge(0), Err(Error::NotAllowed));
+
+            use_account!(alice!());
+            nft.cancel_challenge(0).unwrap();
+
+            assert_eq!(account_balance(alice!()), alice_balance + 50);
+            assert_eq!(nft.challenge_of(0), None);
+            assert!(!nft.is_locked(seed!(0)));
+            assert_eq!(nft.cancel_challenge(0), Err(Error::ChallengeNotFound));
+
+            if let Event::ChallengeCancelled(ChallengeCancelled { id }) = last_event() {
+                assert_eq!(id, 0);
+            } else {
+                panic!("Expected to find ChallengeCancelled event");
+            };
+        }
+
+        #[ink::test]
+        fn cancel_expired_challenge() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            advance_blocks!(CHALLENGE_TIMEOUT + 1);
+
+            use_account!(bob!(), 0);
+            assert_eq!(
+                nft.accept_challenge(0, OPPONENT_ENTROPY),
+                Err(Error::ChallengeExpired)
+            );
+
+            use_account!(charlie!());
+            nft.cancel_challenge(0).unwrap();
+
+            assert!(!nft.is_locked(seed!(0)));
+        }
+
+        #[ink::test]
+        fn cancel_accepted_challenge() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(bob!(), 0);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            use_account!(alice!(), 0);
+            assert_eq!(nft.cancel_challenge(0), Err(Error::ChallengeAccepted));
+        }
+
+        #[ink::test]
+        fn reveal_challenge_with_wrong_secret() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            assert_eq!(
+                nft.reveal_challenge(0, CHALLENGE_SECRET),
+                Err(Error::ChallengeNotAccepted)
+            );
+
+            use_account!(bob!(), 0);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            assert_eq!(
+                nft.reveal_challenge(0, CHALLENGE_SECRET),
+                Err(Error::NotAllowed)
+            );
+
+            use_account!(alice!(), 0);
+            assert_eq!(
+                nft.reveal_challenge(0, [0x08; 32]),
+                Err(Error::InvalidReveal)
+            );
+            assert!(nft.challenge_of(0).is_some());
+        }
+
+        #[ink::test]
+        fn claim_forfeit() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+            nft.start_season().unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(charlie!(), 0);
+            assert_eq!(nft.claim_forfeit(0), Err(Error::ChallengeNotAccepted));
+
+            use_account!(bob!(), 50);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+            set_balance(contract_id(), 100);
+            let bob_balance = account_balance(bob!());
+
+            advance_blocks!(CHALLENGE_REVEAL_TIMEOUT);
+
+            use_account!(charlie!(), 0);
+            assert_eq!(nft.claim_forfeit(0), Err(Error::NotAllowed));
+
+            advance_blocks!(1);
+
+            use_account!(alice!(), 0);
+            assert_eq!(
+                nft.reveal_challenge(0, CHALLENGE_SECRET),
+                Err(Error::ChallengeExpired)
+            );
+
+            use_account!(charlie!(), 0);
+            nft.claim_forfeit(0).unwrap();
+
+            assert_eq!(account_balance(bob!()), bob_balance + 100);
+            assert_eq!(nft.challenge_of(0), None);
+            assert_eq!(nft.rating_of(bob!()), 1216);
+            assert_eq!(nft.rating_of(alice!()), 1184);
+            assert!(!nft.is_locked(seed!(0)));
+            assert!(!nft.is_locked(seed!(1)));
+
+            if let Event::ChallengeSettled(ChallengeSettled { id, winner, payout }) = last_event() {
+                assert_eq!(id, 0);
+                assert_eq!(winner, bob!());
+                assert_eq!(payout, 100);
+            } else {
+                panic!("Expected to find ChallengeSettled event");
+            };
+        }
+
+        #[test]
+        fn elo_update() {
+            assert_eq!(elo::expected(1200, 1200), 500);
+            assert_eq!(elo::expected(1600, 1200), 909);
+            assert_eq!(elo::expected(1200, 1600), 91);
+            assert_eq!(elo::expected(1210, 1200), 514);
+            assert_eq!(elo::expected(3000, 0), 990);
+
+            assert_eq!(elo::update(1200, 1200), (1216, 1184));
+            // upsets move ratings more
+            assert_eq!(elo::update(1200, 1600), (1229, 1571));
+            assert_eq!(elo::update(1600, 1200), (1603, 1197));
+            assert_eq!(elo::update(1200, 10), (1200, 10));
+        }
+
+        #[ink::test]
+        fn start_season() {
+            let mut nft = PokeNFT::new();
+
+            assert_eq!(nft.current_season(), None);
+            assert_eq!(nft.start_season(), Ok(1));
+            assert_eq!(nft.current_season(), Some(1));
+            assert_eq!(nft.start_season(), Err(Error::SeasonInProgress));
+
+            if let Event::SeasonStarted(SeasonStarted { season }) = last_event() {
+                assert_eq!(season, 1);
+            } else {
+                panic!("Expected to find SeasonStarted event");
+            };
+
+            use_account!(bob!());
+            assert_eq!(nft.end_season(), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn start_season_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.start_season(), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn ranked_match() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+            nft.start_season().unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(bob!(), 50);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            set_balance(contract_id(), 100);
+            use_account!(alice!(), 0);
+            let outcome = nft.reveal_challenge(0, CHALLENGE_SECRET).unwrap();
+
+            let (winner, loser) = if outcome.winner == seed!(0) {
+                (alice!(), bob!())
+            } else {
+                (bob!(), alice!())
+            };
+
+            assert_eq!(nft.rating_of(winner), 1216);
+            assert_eq!(nft.rating_of(loser), 1184);
+            assert_eq!(nft.rating_of(charlie!()), elo::INITIAL);
+            assert_eq!(nft.token_rating_of(outcome.winner), 1216);
+            assert_eq!(nft.top_ratings(1, 10), vec![(winner, 1216), (loser, 1184)]);
+            assert_eq!(nft.top_ratings(1, 1), vec![(winner, 1216)]);
+            assert_eq!(nft.top_ratings(2, 10), Vec::<(AccountId, Rating)>::new());
+        }
+
+        #[ink::test]
+        fn unranked_match_outside_season() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(bob!(), 50);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            set_balance(contract_id(), 100);
+            use_account!(alice!(), 0);
+            let outcome = nft.reveal_challenge(0, CHALLENGE_SECRET).unwrap();
+
+            assert_eq!(nft.rating_of(alice!()), elo::INITIAL);
+            assert_eq!(nft.rating_of(bob!()), elo::INITIAL);
+            assert_eq!(nft.token_rating_of(outcome.winner), 1216);
+        }
+
+        #[ink::test]
+        fn unstaked_match() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+            nft.start_season().unwrap();
+
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(bob!(), 0);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            use_account!(alice!(), 0);
+            let outcome = nft.reveal_challenge(0, CHALLENGE_SECRET).unwrap();
+
+            // free challenges are settled like casual battles
+            assert_eq!(nft.experience_of(outcome.winner), 0);
+            assert_eq!(nft.rating_of(alice!()), elo::INITIAL);
+            assert_eq!(nft.rating_of(bob!()), elo::INITIAL);
+            assert_eq!(nft.token_rating_of(outcome.winner), elo::INITIAL);
+            assert_eq!(nft.challenge_of(0), None);
+        }
+
+        #[ink::test]
+        fn end_season() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+
+            assert_eq!(nft.end_season(), Err(Error::NoActiveSeason));
+
+            nft.start_season().unwrap();
+
+            use_account!(alice!(), 50);
+            nft.challenge(seed!(0), bob!(), seed!(1), 50, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            use_account!(bob!(), 50);
+            nft.accept_challenge(0, OPPONENT_ENTROPY).unwrap();
+
+            set_balance(contract_id(), 100);
+            use_account!(alice!(), 0);
+            let outcome = nft.reveal_challenge(0, CHALLENGE_SECRET).unwrap();
+            let (winner, loser) = if outcome.winner == seed!(0) {
+                (alice!(), bob!())
+            } else {
+                (bob!(), alice!())
+            };
+
+            use_account!(alice!());
+            nft.end_season().unwrap();
+
+            assert_eq!(nft.current_season(), None);
+            assert_eq!(nft.top_ratings(1, 10), vec![(winner, 1216), (loser, 1184)]);
+
+            let events = recorded_events().count();
+
+            if let Event::SeasonEnded(SeasonEnded { season }) = get_event(events - 3) {
+                assert_eq!(season, 1);
+            } else {
+                panic!("Expected to find SeasonEnded event");
+            };
+
+            if let Event::SeasonReward(SeasonReward {
+                season,
+                rank,
+                account,
+                rating,
+            }) = get_event(events - 2)
+            {
+                assert_eq!(season, 1);
+                assert_eq!(rank, 1);
+                assert_eq!(account, winner);
+                assert_eq!(rating, 1216);
+            } else {
+                panic!("Expected to find SeasonReward event");
+            };
+
+            if let Event::SeasonReward(SeasonReward { rank, account, .. }) = last_event() {
+                assert_eq!(rank, 2);
+                assert_eq!(account, loser);
+            } else {
+                panic!("Expected to find SeasonReward event");
+            };
+
+            // a new season starts everyone over, the old standings stay around
+            nft.start_season().unwrap();
+
+            assert_eq!(nft.rating_of(winner), elo::INITIAL);
+            assert_eq!(nft.top_ratings(1, 1), vec![(winner, 1216)]);
+            assert_eq!(nft.top_ratings(2, 10), Vec::<(AccountId, Rating)>::new());
+        }
+
+        #[ink::test]
+        fn create_tournament() {
+            let mut nft = PokeNFT::new();
+
+            assert_eq!(nft.create_tournament(100, 8), Ok(0));
+            assert_eq!(nft.create_tournament(0, 2), Ok(1));
+
+            assert_eq!(
+                nft.tournament(0),
+                Some(Tournament {
+                    entry_fee: 100,
+                    max_entrants: 8,
+                    prize_pool: 0,
+                    round: 0,
+                    entrants: Vec::new(),
+                    registrants: Vec::new(),
+                    winner: None,
+                    finished: false,
+                })
+            );
+
+            if let Event::TournamentCreated(TournamentCreated {
+                id,
+                entry_fee,
+                max_entrants,
+            }) = last_event()
+            {
+                assert_eq!(id, 1);
+                assert_eq!(entry_fee, 0);
+                assert_eq!(max_entrants, 2);
+            } else {
+                panic!("Expected to find TournamentCreated event");
+            };
+        }
+
+        #[ink::test]
+        fn create_tournament_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.create_tournament(100, 8), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn register() {
+            let mut nft = PokeNFT::new();
+            nft.create_tournament(100, 2).unwrap();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.mint(seed!(2)).unwrap();
+
+            use_account!(alice!(), 100);
+            nft.register(0, seed!(0)).unwrap();
+
+            assert!(nft.is_locked(seed!(0)));
+            assert_eq!(nft.transfer(bob!(), seed!(0)), Err(Error::TokenLocked));
+            assert_eq!(nft.register(0, seed!(0)), Err(Error::TokenLocked));
+
+            if let Event::TournamentEntered(TournamentEntered { id, owner, seed }) = last_event() {
+                assert_eq!(id, 0);
+                assert_eq!(owner, alice!());
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find TournamentEntered event");
+            };
+
+            nft.register(0, seed!(1)).unwrap();
+
+            let tournament = nft.tournament(0).unwrap();
+            assert_eq!(tournament.entrants, vec![seed!(0), seed!(1)]);
+            assert_eq!(tournament.prize_pool, 200);
+
+            assert_eq!(nft.register(0, seed!(2)), Err(Error::TournamentFull));
+        }
+
+        #[ink::test]
+        fn register_with_invalid_entry() {
+            let mut nft = PokeNFT::new();
+            nft.create_tournament(100, 8).unwrap();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            use_account!(alice!(), 50);
+            assert_eq!(nft.register(0, seed!(0)), Err(Error::InvalidPrice));
+            assert_eq!(nft.register(1, seed!(0)), Err(Error::TournamentNotFound));
+            assert_eq!(nft.register(0, seed!(2)), Err(Error::TokenNotFound));
+
+            use_account!(bob!(), 100);
+            assert_eq!(nft.register(0, seed!(0)), Err(Error::NotOwner));
+
+            use_account!(alice!(), 100);
+            nft.register(0, seed!(0)).unwrap();
+            set_balance(contract_id(), 100);
+            nft.advance_round(0).unwrap();
+
+            assert_eq!(nft.register(0, seed!(1)), Err(Error::TournamentStarted));
+        }
+
+        #[ink::test]
+        fn advance_round() {
+            let mut nft = PokeNFT::new();
+            nft.create_tournament(100, 8).unwrap();
+            nft.mint_batch(vec![seed!(0), seed!(1), seed!(2)]).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+            nft.transfer(charlie!(), seed!(2)).unwrap();
+
+            for (account, seed) in [
+                (alice!(), seed!(0)),
+                (bob!(), seed!(1)),
+                (charlie!(), seed!(2)),
+            ]
+            .iter()
+            {
+                use_account!(*account, 100);
+                nft.register(0, *seed).unwrap();
+            }
+
+            use_account!(bob!());
+            assert_eq!(nft.advance_round(0), Err(Error::NotAllowed));
+
+            use_account!(alice!());
+            nft.advance_round(0).unwrap();
+
+            // seed 2 gets a bye
+            let tournament = nft.tournament(0).unwrap();
+            let semifinal_winner = tournament.entrants[0];
+            let semifinal_loser = if semifinal_winner == seed!(0) {
+                seed!(1)
+            } else {
+                seed!(0)
+            };
+
+            assert_eq!(tournament.round, 1);
+            assert_eq!(tournament.entrants, vec![semifinal_winner, seed!(2)]);
+            assert!(!tournament.finished);
+            assert!(!nft.is_locked(semifinal_loser));
+            assert!(nft.is_locked(semifinal_winner));
+            assert!(nft.is_locked(seed!(2)));
+
+            if let Event::RoundAdvanced(RoundAdvanced {
+                id,
+                round,
+                remaining,
+            }) = last_event()
+            {
+                assert_eq!(id, 0);
+                assert_eq!(round, 1);
+                assert_eq!(remaining, vec![semifinal_winner, seed!(2)]);
+            } else {
+                panic!("Expected to find RoundAdvanced event");
+            };
+
+            set_balance(contract_id(), 300);
+            let balances: Vec<Balance> = [alice!(), bob!(), charlie!()]
+                .iter()
+                .map(|account| account_balance(*account))
+                .collect();
+            nft.advance_round(0).unwrap();
+
+            let tournament = nft.tournament(0).unwrap();
+            let winner = tournament.winner.unwrap();
+            let owner = [alice!(), bob!(), charlie!()]
+                .iter()
+                .position(|account| *account == nft.owner_of(winner))
+                .unwrap();
+
+            assert!(winner == semifinal_winner || winner == seed!(2));
+            assert!(tournament.finished);
+            assert_eq!(tournament.prize_pool, 0);
+            assert_eq!(tournament.entrants, vec![winner]);
+            assert!(!nft.is_locked(winner));
+            assert_eq!(account_balance(nft.owner_of(winner)), balances[owner] + 300);
+
+            if let Event::TournamentWon(TournamentWon {
+                id,
+                winner: won,
+                prize,
+            }) = last_event()
+            {
+                assert_eq!(id, 0);
+                assert_eq!(won, Some(winner));
+                assert_eq!(prize, 300);
+            } else {
+                panic!("Expected to find TournamentWon event");
+            };
+
+            assert_eq!(nft.advance_round(0), Err(Error::TournamentFinished));
+        }
+
+        #[ink::test]
+        fn advance_round_records_matches() {
+            let mut nft = PokeNFT::new();
+            nft.create_tournament(0, 2).unwrap();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
+            nft.start_season().unwrap();
+
+            use_account!(alice!(), 0);
+            nft.register(0, seed!(0)).unwrap();
+            use_account!(bob!(), 0);
+            nft.register(0, seed!(1)).unwrap();
+
+            use_account!(alice!());
+            nft.advance_round(0).unwrap();
+
+            let winner = nft.tournament(0).unwrap().winner.unwrap();
+            let (winner_account, loser_account, loser) = if winner == seed!(0) {
+                (alice!(), bob!(), seed!(1))
+            } else {
+                (bob!(), alice!(), seed!(0))
+            };
+
+            assert_eq!(nft.rating_of(winner_account), 1216);
+            assert_eq!(nft.rating_of(loser_account), 1184);
+            assert_eq!(nft.token_rating_of(winner), 1216);
+            assert_eq!(nft.token_rating_of(loser), 1184);
+        }
+
+        #[ink::test]
+        fn advance_round_without_entrants() {
+            let mut nft = PokeNFT::new();
+            nft.create_tournament(100, 8).unwrap();
+
+            nft.advance_round(0).unwrap();
+
+            let tournament = nft.tournament(0).unwrap();
+            assert!(tournament.finished);
+            assert_eq!(tournament.winner, None);
+            assert_eq!(nft.advance_round(1), Err(Error::TournamentNotFound));
+        }
+
+        #[test]
+        fn experience_levels() {
+            assert_eq!(battle::level(0), 1);
+            assert_eq!(battle::level(7), 1);
+            assert_eq!(battle::level(8), 2);
+            assert_eq!(battle::level(124_999), 49);
+            assert_eq!(battle::level(125_000), 50);
+            assert_eq!(battle::level(u32::MAX), battle::MAX_LEVEL);
+
+            assert_eq!(battle::experience_yield(1), 9);
+            assert_eq!(battle::experience_yield(50), 457);
+        }
+
+        #[ink::test]
+        fn level_of() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(nft.level_of(seed!(0)), 1);
+            assert_eq!(nft.experience_of(seed!(0)), 0);
+
+            nft.grant_experience(seed!(0), 1_000).unwrap();
+
+            assert_eq!(nft.level_of(seed!(0)), 10);
+            assert_eq!(nft.experience_of(seed!(0)), 1_000);
+
+            if let Event::LevelUp(LevelUp { seed, level }) = last_event() {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(level, 10);
+            } else {
+                panic!("Expected to find LevelUp event");
+            }
+
+            // experience stops at level 100
+            nft.grant_experience(seed!(0), u32::MAX).unwrap();
+
+            assert_eq!(nft.level_of(seed!(0)), 100);
+            assert_eq!(nft.experience_of(seed!(0)), 1_000_000);
+        }
+
+        #[ink::test]
+        fn grant_experience_without_level_up() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            let events = recorded_events().count();
+
+            nft.grant_experience(seed!(0), 7).unwrap();
+
+            assert_eq!(nft.level_of(seed!(0)), 1);
+            assert_eq!(recorded_events().count(), events);
+            assert_eq!(nft.grant_experience(seed!(1), 7), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn grant_experience_while_not_admin() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.grant_experience(seed!(0), 100), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn experience_follows_transfers() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.grant_experience(seed!(0), 8_000).unwrap();
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.level_of(seed!(0)), 20);
+        }
+
+        #[ink::test]
+        fn stats_of() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            assert_eq!(
+                nft.stats_of(seed!(0)),
+                battle::stats(72, rng::ivs(seed!(0)), 1).ok()
+            );
+
+            nft.grant_experience(seed!(0), 125_000).unwrap();
+
+            // Tentacool at level 50 with all DVs at 0
+            assert_eq!(
+                nft.stats_of(seed!(0)),
+                Some(Stats {
+                    hp: 100,
+                    attack: 45,
+                    defense: 40,
+                    speed: 75,
+                    special: 105,
+                })
+            );
+            assert_eq!(nft.stats_of(seed!(1)), None);
+        }
+
+        #[ink::test]
+        fn deposit_daycare() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+
+            nft.deposit_daycare(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), contract_id());
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(1)]);
+            assert_eq!(nft.staked_of(alice!()), vec![seed!(0)]);
+            assert_eq!(nft.staked_of(bob!()), Vec::<Seed>::new());
+            assert_eq!(
+                nft.daycare_of(seed!(0)),
+                Some(DaycareStay {
+                    owner: alice!(),
+                    since: 0,
+                })
+            );
+            assert_eq!(nft.transfer(bob!(), seed!(0)), Err(Error::NotAllowed));
+
+            if let Event::DaycareDeposited(DaycareDeposited { seed, owner }) = last_event() {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(owner, alice!());
+            } else {
+                panic!("Expected to find DaycareDeposited event");
+            };
+        }
+
+        #[ink::test]
+        fn deposit_daycare_while_not_owner() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.deposit_daycare(seed!(0)), Err(Error::NotAllowed));
+            assert_eq!(nft.deposit_daycare(seed!(1)), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn withdraw_daycare() {
+            let mut nft = PokeNFT::new();
+            nft.set_daycare_fee(10).unwrap();
+            nft.mint(seed!(0)).unwrap();
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            use_account!(bob!());
+            nft.deposit_daycare(seed!(0)).unwrap();
+
+            advance_blocks!(1_000);
+
+            // level 1 to 10
+            assert_eq!(nft.daycare_fee_of(seed!(0)), Some(90));
+
+            set_balance(contract_id(), 90);
+            let admin_balance = account_balance(alice!());
+
+            use_account!(bob!(), 50);
+            assert_eq!(nft.withdraw_daycare(seed!(0)), Err(Error::InvalidPrice));
+
+            use_account!(charlie!(), 90);
+            assert_eq!(nft.withdraw_daycare(seed!(0)), Err(Error::NotAllowed));
+
+            use_account!(bob!(), 90);
+            nft.withdraw_daycare(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.staked_of(bob!()), Vec::<Seed>::new());
+            assert_eq!(nft.daycare_of(seed!(0)), None);
+            assert_eq!(nft.experience_of(seed!(0)), 1_000);
+            assert_eq!(nft.level_of(seed!(0)), 10);
+            assert_eq!(account_balance(alice!()), admin_balance + 90);
+            assert_eq!(nft.withdraw_daycare(seed!(0)), Err(Error::NotInDaycare));
+
+            if let Event::DaycareWithdrawn(DaycareWithdrawn {
+                seed,
+                owner,
+                experience,
+                fee,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(owner, bob!());
+                assert_eq!(experience, 1_000);
+                assert_eq!(fee, 90);
+            } else {
+                panic!("Expected to find DaycareWithdrawn event");
+            };
+        }
+
+        #[ink::test]
+        fn daycare_experience_is_capped() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.deposit_daycare(seed!(0)).unwrap();
+
+            advance_blocks!(DAYCARE_EXPERIENCE_CAP + 100);
+
+            use_account!(alice!(), 0);
+            nft.withdraw_daycare(seed!(0)).unwrap();
+
+            assert_eq!(nft.experience_of(seed!(0)), DAYCARE_EXPERIENCE_CAP);
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+        #[ink::test]
+        fn set_daycare_fee_while_not_admin() {
+            let mut nft = PokeNFT::new();
 
-        use ink_env::test::{default_accounts, push_execution_context, recorded_events};
-        use ink_env::{self, DefaultEnvironment};
-        use ink_lang as ink;
+            use_account!(bob!());
 
-        macro_rules! zero_account {
-            () => {
-                AccountId::from([0x0; 32])
-            };
+            assert_eq!(nft.set_daycare_fee(10), Err(Error::NotAllowed));
         }
 
-        macro_rules! alice {
-            () => {
-                default_accounts::<DefaultEnvironment>().unwrap().alice
+        #[ink::test]
+        fn lock() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            nft.lock(seed!(0), LockReason::Custom(7), 10).unwrap();
+
+            assert!(nft.is_locked(seed!(0)));
+            assert_eq!(
+                nft.locks_of(seed!(0)),
+                vec![Lock {
+                    reason: LockReason::Custom(7),
+                    locker: alice!(),
+                    until: 10,
+                }]
+            );
+            assert_eq!(nft.transfer(bob!(), seed!(0)), Err(Error::TokenLocked));
+            assert_eq!(nft.approve(bob!(), seed!(0)), Err(Error::TokenLocked));
+            assert_eq!(
+                nft.approve_batch(bob!(), vec![seed!(0)]),
+                Err(BatchError::at(0, Error::TokenLocked))
+            );
+            assert_eq!(nft.burn(seed!(0)), Err(Error::TokenLocked));
+
+            if let Event::Locked(Locked {
+                seed,
+                reason,
+                locker,
+                until,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(reason, LockReason::Custom(7));
+                assert_eq!(locker, alice!());
+                assert_eq!(until, 10);
+            } else {
+                panic!("Expected to find Locked event");
             };
         }
 
-        macro_rules! bob {
-            () => {
-                default_accounts::<DefaultEnvironment>().unwrap().bob
-            };
+        #[ink::test]
+        fn lock_expires() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.lock(seed!(0), LockReason::Custom(0), 10).unwrap();
+
+            advance_blocks!(9);
+            assert!(nft.is_locked(seed!(0)));
+
+            advance_blocks!(1);
+            assert!(!nft.is_locked(seed!(0)));
+            assert_eq!(nft.locks_of(seed!(0)), Vec::<Lock>::new());
+            assert_eq!(
+                nft.unlock(seed!(0), LockReason::Custom(0)),
+                Err(Error::LockNotFound)
+            );
+
+            nft.transfer(bob!(), seed!(0)).unwrap();
         }
 
-        macro_rules! charlie {
-            () => {
-                default_accounts::<DefaultEnvironment>().unwrap().charlie
+        #[ink::test]
+        fn lock_with_invalid_deadline() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            advance_blocks!(5);
+
+            assert_eq!(
+                nft.lock(seed!(0), LockReason::Custom(0), 5),
+                Err(Error::InvalidDeadline)
+            );
+            assert_eq!(
+                nft.lock(seed!(0), LockReason::Custom(0), 6 + MAX_LOCK_DURATION),
+                Err(Error::InvalidDeadline)
+            );
+
+            nft.lock(seed!(0), LockReason::Custom(0), 5 + MAX_LOCK_DURATION)
+                .unwrap();
+        }
+
+        #[ink::test]
+        fn lock_while_not_owner() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.approve(charlie!(), seed!(1)).unwrap();
+
+            use_account!(bob!());
+            assert_eq!(
+                nft.lock(seed!(0), LockReason::Custom(0), 10),
+                Err(Error::NotAllowed)
+            );
+
+            // being approved isn't enough to lock a token
+            use_account!(charlie!());
+            assert_eq!(
+                nft.lock(seed!(1), LockReason::Custom(0), 10),
+                Err(Error::NotAllowed)
+            );
+        }
+
+        #[ink::test]
+        fn lock_as_game() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(1)).unwrap();
+            nft.set_game(charlie!(), true).unwrap();
+
+            // the owner can't lift locks placed by games
+            use_account!(charlie!());
+            nft.lock(seed!(1), LockReason::Custom(0), 10).unwrap();
+
+            use_account!(alice!());
+            assert_eq!(
+                nft.unlock(seed!(1), LockReason::Custom(0)),
+                Err(Error::LockNotFound)
+            );
+            assert!(nft.is_locked(seed!(1)));
+        }
+
+        #[ink::test]
+        fn lock_loan_collateral() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
+            nft.set_game(charlie!(), true).unwrap();
+
+            set_balance(contract_id(), 100);
+            use_account!(bob!(), 100);
+            nft.fund_loan(seed!(0), 10, 50).unwrap();
+
+            // a game can't hold the collateral hostage until the loan defaults
+            use_account!(charlie!());
+            assert_eq!(
+                nft.lock(seed!(0), LockReason::Custom(0), 10),
+                Err(Error::NotAllowed)
+            );
+            assert!(!nft.is_locked(seed!(0)));
+
+            set_balance(contract_id(), 110);
+            use_account!(alice!(), 110);
+            nft.repay_loan(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+        }
+
+        #[ink::test]
+        fn unlock() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.lock(seed!(0), LockReason::Custom(0), 10).unwrap();
+            nft.lock(seed!(0), LockReason::Custom(1), 20).unwrap();
+            // re-locking moves the deadline rather than adding another lock
+            nft.lock(seed!(0), LockReason::Custom(1), 30).unwrap();
+
+            assert_eq!(nft.locks_of(seed!(0)).len(), 2);
+
+            nft.unlock(seed!(0), LockReason::Custom(0)).unwrap();
+
+            assert_eq!(
+                nft.locks_of(seed!(0)),
+                vec![Lock {
+                    reason: LockReason::Custom(1),
+                    locker: alice!(),
+                    until: 30,
+                }]
+            );
+
+            if let Event::Unlocked(Unlocked {
+                seed,
+                reason,
+                locker,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(reason, LockReason::Custom(0));
+                assert_eq!(locker, alice!());
+            } else {
+                panic!("Expected to find Unlocked event");
             };
+
+            nft.unlock(seed!(0), LockReason::Custom(1)).unwrap();
+
+            assert!(!nft.is_locked(seed!(0)));
+            nft.transfer(bob!(), seed!(0)).unwrap();
         }
 
-        macro_rules! use_account {
-            ($account:expr) => {
-                let mut data =
-                    ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4]));
-                data.push_arg(&$account);
+        #[ink::test]
+        fn unlock_challenge_lock_while_owner() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.transfer(bob!(), seed!(1)).unwrap();
 
-                push_execution_context::<DefaultEnvironment>(
-                    $account,
-                    ink_env::account_id::<ink_env::DefaultEnvironment>()
-                        .unwrap_or([0x0; 32].into()),
-                    1000000,
-                    1000000,
-                    data,
-                );
+            use_account!(alice!(), 0);
+            nft.challenge(seed!(0), bob!(), seed!(1), 0, commitment(CHALLENGE_SECRET))
+                .unwrap();
+
+            assert_eq!(
+                nft.locks_of(seed!(0)),
+                vec![Lock {
+                    reason: LockReason::Challenge,
+                    locker: contract_id(),
+                    until: CHALLENGE_TIMEOUT + 1,
+                }]
+            );
+            assert_eq!(
+                nft.unlock(seed!(0), LockReason::Challenge),
+                Err(Error::LockNotFound)
+            );
+
+            // the challenge lock runs out with the challenge
+            advance_blocks!(CHALLENGE_TIMEOUT + 1);
+
+            assert!(!nft.is_locked(seed!(0)));
+        }
+
+        #[ink::test]
+        fn burn() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.approve(bob!(), seed!(0)).unwrap();
+            nft.grant_experience(seed!(0), 100).unwrap();
+
+            nft.burn(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), zero_account!());
+            assert_eq!(nft.tokens_of(alice!()), vec![seed!(1)]);
+            assert_eq!(nft.get_approved(seed!(0)), None);
+            assert_eq!(nft.experience_of(seed!(0)), 0);
+            assert_eq!(nft.burn(seed!(0)), Err(Error::TokenNotFound));
+
+            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
+                assert_eq!(from, Some(alice!()));
+                assert_eq!(to, None);
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find Transfer event");
             };
         }
 
-        macro_rules! seed {
-            ($seed:expr) => {
-                [$seed; 32]
+        #[ink::test]
+        fn mint_burned_seed() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.burn(seed!(0)).unwrap();
+
+            use_account!(bob!());
+            assert_eq!(nft.mint(seed!(0)), Err(Error::TokenBurned));
+            assert_eq!(
+                nft.mint_batch(vec![seed!(1), seed!(0)]),
+                Err(BatchError {
+                    index: Some(1),
+                    error: Error::TokenBurned
+                })
+            );
+            assert_eq!(nft.owner_of(seed!(0)), zero_account!());
+            assert_eq!(nft.balance_of(bob!()), 0);
+        }
+
+        #[ink::test]
+        fn burn_while_not_owner() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.burn(seed!(0)), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn set_game() {
+            let mut nft = PokeNFT::new();
+
+            assert!(!nft.is_game(charlie!()));
+
+            nft.set_game(charlie!(), true).unwrap();
+            assert!(nft.is_game(charlie!()));
+
+            nft.set_game(charlie!(), false).unwrap();
+            assert!(!nft.is_game(charlie!()));
+
+            assert_eq!(
+                nft.set_game(zero_account!(), true),
+                Err(Error::InvalidAddress)
+            );
+        }
+
+        #[ink::test]
+        fn set_game_while_not_admin() {
+            let mut nft = PokeNFT::new();
+
+            use_account!(bob!());
+
+            assert_eq!(nft.set_game(bob!(), true), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn set_attribute() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_game(bob!(), true).unwrap();
+            nft.set_game(charlie!(), true).unwrap();
+
+            use_account!(bob!());
+            nft.set_attribute(seed!(0), String::from("quest"), vec![1])
+                .unwrap();
+            nft.set_attribute(seed!(0), String::from("quest"), vec![2])
+                .unwrap();
+
+            if let Event::AttributeSet(AttributeSet {
+                seed,
+                namespace,
+                key,
+                value,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(namespace, bob!());
+                assert_eq!(key, String::from("quest"));
+                assert_eq!(value, vec![2]);
+            } else {
+                panic!("Expected to find AttributeSet event");
             };
+
+            // same key, different namespace
+            use_account!(charlie!());
+            nft.set_attribute(seed!(0), String::from("quest"), vec![3])
+                .unwrap();
+
+            assert_eq!(
+                nft.attributes_of(seed!(0)),
+                vec![
+                    Attribute {
+                        namespace: bob!(),
+                        key: String::from("quest"),
+                        value: vec![2],
+                    },
+                    Attribute {
+                        namespace: charlie!(),
+                        key: String::from("quest"),
+                        value: vec![3],
+                    },
+                ]
+            );
+            assert_eq!(
+                nft.attribute(seed!(0), bob!(), String::from("quest")),
+                Some(vec![2])
+            );
+            assert_eq!(nft.attribute(seed!(0), bob!(), String::from("level")), None);
+            assert_eq!(nft.attributes_of(seed!(1)), Vec::<Attribute>::new());
         }
 
-        fn get_event(idx: usize) -> Event {
-            let raw_event = recorded_events()
-                .nth(idx)
-                .expect(&format!("No event found"));
+        #[ink::test]
+        fn set_attribute_limits() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_game(bob!(), true).unwrap();
+            nft.set_game(charlie!(), true).unwrap();
 
-            <Event as scale::Decode>::decode(&mut &raw_event.data[..])
-                .expect("Invalid contract Event")
+            use_account!(charlie!());
+            assert_eq!(
+                nft.set_attribute(seed!(0), "k".repeat(33), vec![]),
+                Err(Error::AttributeTooLarge)
+            );
+            assert_eq!(
+                nft.set_attribute(seed!(0), String::from("key"), vec![0; 257]),
+                Err(Error::AttributeTooLarge)
+            );
+
+            for idx in 0..MAX_ATTRIBUTES_PER_GAME {
+                nft.set_attribute(seed!(0), format!("key{}", idx), vec![0; 256])
+                    .unwrap();
+            }
+
+            assert_eq!(
+                nft.set_attribute(seed!(0), String::from("extra"), vec![]),
+                Err(Error::TooManyAttributes)
+            );
+            // existing keys can still be overwritten
+            nft.set_attribute(seed!(0), String::from("key0"), vec![1])
+                .unwrap();
+
+            // the limit is per game
+            use_account!(bob!());
+            nft.set_attribute(seed!(0), String::from("extra"), vec![])
+                .unwrap();
+
+            assert_eq!(
+                nft.attributes_of(seed!(0)).len() as u32,
+                MAX_ATTRIBUTES_PER_GAME + 1
+            );
         }
 
-        fn last_event() -> Event {
-            get_event(recorded_events().count() - 1)
+        #[ink::test]
+        fn set_attribute_while_not_game() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_game(bob!(), true).unwrap();
+
+            // not even the owner or admin
+            assert_eq!(
+                nft.set_attribute(seed!(0), String::from("quest"), vec![1]),
+                Err(Error::NotAllowed)
+            );
+
+            use_account!(bob!());
+            assert_eq!(
+                nft.set_attribute(seed!(1), String::from("quest"), vec![1]),
+                Err(Error::TokenNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn attributes_follow_transfers() {
+            let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_game(charlie!(), true).unwrap();
+
+            use_account!(charlie!());
+            nft.set_attribute(seed!(0), String::from("quest"), vec![1])
+                .unwrap();
+
+            use_account!(alice!());
+            nft.transfer(bob!(), seed!(0)).unwrap();
+
+            assert_eq!(
+                nft.attribute(seed!(0), charlie!(), String::from("quest")),
+                Some(vec![1])
+            );
         }
 
         #[ink::test]
-        fn mint() {
+        fn grant_experience_as_game() {
             let mut nft = PokeNFT::new();
-            nft.mint(seed!(10)).unwrap();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_game(charlie!(), true).unwrap();
 
-            assert_eq!(nft.seeds.get(&seed!(10)), Some(&(alice!(), 16)));
+            use_account!(charlie!());
+            nft.grant_experience(seed!(0), 8).unwrap();
 
-            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
-                assert_eq!(from, None);
-                assert_eq!(to, Some(alice!()));
-                assert_eq!(seed, seed!(10));
-            } else {
-                panic!("Expected to find Transfer event");
-            };
+            assert_eq!(nft.level_of(seed!(0)), 2);
         }
 
         #[ink::test]
-        fn cant_mint_twice() {
+        fn interface() {
             let mut nft = PokeNFT::new();
-            nft.mint(seed!(1)).unwrap();
+            nft.mint(seed!(0)).unwrap();
 
-            let result = nft.mint(seed!(1));
+            assert_eq!(PokeNftInterface::owner_of(&nft, seed!(0)), alice!());
+            assert_eq!(PokeNftInterface::pokemon_of(&nft, seed!(0)), 72);
 
-            assert_eq!(result, Err(Error::TokenAlreadyExists));
+            PokeNftInterface::transfer_from(&mut nft, alice!(), bob!(), seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(
+                PokeNftInterface::transfer_from(&mut nft, bob!(), alice!(), seed!(0)),
+                Err(Error::NotAllowed)
+            );
         }
 
         #[ink::test]
-        fn transfer() {
+        fn set_user() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
 
-            nft.transfer(bob!(), seed!(0)).unwrap();
+            assert_eq!(nft.user_of(seed!(0)), None);
 
-            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
-            if let Event::Transfer(Transfer { from, to, seed }) = last_event() {
-                assert_eq!(from, Some(alice!()));
-                assert_eq!(to, Some(bob!()));
+            assert_eq!(nft.user_of(seed!(0)), Some(bob!()));
+            assert_eq!(nft.user_expires(seed!(0)), 10);
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+
+            if let Event::UpdateUser(UpdateUser {
+                seed,
+                user,
+                expires,
+            }) = last_event()
+            {
                 assert_eq!(seed, seed!(0));
+                assert_eq!(user, Some(bob!()));
+                assert_eq!(expires, 10);
             } else {
-                panic!("Expected to find Transfer event");
+                panic!("Expected to find UpdateUser event");
             };
+
+            nft.set_user(seed!(0), None, 0).unwrap();
+
+            assert_eq!(nft.user_of(seed!(0)), None);
+            assert_eq!(nft.user_expires(seed!(0)), 0);
         }
 
         #[ink::test]
-        fn transfer_someone_elses_token() {
+        fn set_user_while_not_owner() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
+            // users can't pass the token on
             use_account!(bob!());
 
-            let result = nft.transfer(alice!(), seed!(0));
-
-            assert_eq!(result, Err(Error::NotAllowed));
+            assert_eq!(
+                nft.set_user(seed!(0), Some(charlie!()), 10),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft.transfer(charlie!(), seed!(0)), Err(Error::NotAllowed));
         }
 
         #[ink::test]
-        fn approved_transfers_from() {
+        fn set_user_with_invalid_deadline() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
-            nft.approve(bob!(), seed!(0)).unwrap();
-
-            use_account!(bob!());
-            let result = nft.transfer_from(alice!(), charlie!(), seed!(0));
+            advance_blocks!(5);
 
-            assert_eq!(result, Ok(()));
-            assert_eq!(nft.owner_of(seed!(0)), charlie!());
+            assert_eq!(
+                nft.set_user(seed!(0), Some(bob!()), 4),
+                Err(Error::InvalidDeadline)
+            );
+            assert_eq!(
+                nft.set_user(seed!(0), Some(zero_account!()), 10),
+                Err(Error::InvalidAddress)
+            );
         }
 
         #[ink::test]
-        fn unapproved_transfers_from() {
+        fn user_expires() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
-            use_account!(bob!());
-            let result = nft.transfer_from(alice!(), charlie!(), seed!(0));
+            advance_blocks!(10);
+            assert_eq!(nft.user_of(seed!(0)), Some(bob!()));
 
-            assert_eq!(result, Err(Error::NotAllowed));
-            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            advance_blocks!(1);
+            assert_eq!(nft.user_of(seed!(0)), None);
         }
 
         #[ink::test]
-        fn transfer_non_existing_token() {
+        fn transfer_clears_user() {
             let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
-            let result = nft.transfer(bob!(), seed!(0));
+            nft.transfer(charlie!(), seed!(0)).unwrap();
 
-            assert_eq!(result, Err(Error::TokenNotFound));
+            assert_eq!(nft.user_of(seed!(0)), None);
+
+            if let Event::UpdateUser(UpdateUser {
+                seed,
+                user,
+                expires,
+            }) = get_event(recorded_events().count() - 2)
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(user, None);
+                assert_eq!(expires, 0);
+            } else {
+                panic!("Expected to find UpdateUser event");
+            };
         }
 
         #[ink::test]
-        fn transfer_removes_approval() {
+        fn battle_as_user() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
-            nft.approve(bob!(), seed!(0)).unwrap();
+            nft.mint(seed!(1)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
-            nft.transfer(charlie!(), seed!(0)).unwrap();
+            use_account!(bob!());
+            nft.battle(seed!(0), seed!(1)).unwrap();
+            assert_eq!(nft.battle(seed!(1), seed!(0)), Err(Error::NotAllowed));
 
-            assert_eq!(nft.get_approved(seed!(0)), None);
+            advance_blocks!(11);
+            assert_eq!(nft.battle(seed!(0), seed!(1)), Err(Error::NotAllowed));
         }
 
         #[ink::test]
-        fn balance_of() {
+        fn challenge_as_user() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
             nft.mint(seed!(1)).unwrap();
+            nft.transfer(charlie!(), seed!(1)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
+
+            use_account!(bob!(), 0);
+            nft.challenge(
+                seed!(0),
+                charlie!(),
+                seed!(1),
+                0,
+                commitment(CHALLENGE_SECRET),
+            )
+            .unwrap();
+
+            assert_eq!(nft.challenge_of(0).unwrap().challenger, bob!());
+            assert!(nft.is_locked(seed!(0)));
+        }
 
-            assert_eq!(nft.balance_of(alice!()), 2);
-            assert_eq!(nft.balance_of(bob!()), 0);
-
-            nft.transfer(bob!(), seed!(0)).unwrap();
-
-            assert_eq!(nft.balance_of(alice!()), 1);
-            assert_eq!(nft.balance_of(bob!()), 1);
+        #[ink::test]
+        fn register_as_user() {
+            let mut nft = PokeNFT::new();
+            nft.create_tournament(0, 8).unwrap();
+            nft.mint(seed!(0)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
-            use_account!(bob!());
+            use_account!(charlie!(), 0);
+            assert_eq!(nft.register(0, seed!(0)), Err(Error::NotOwner));
 
-            nft.transfer(alice!(), seed!(0)).unwrap();
+            use_account!(bob!(), 0);
+            nft.register(0, seed!(0)).unwrap();
 
-            assert_eq!(nft.balance_of(alice!()), 2);
-            assert_eq!(nft.balance_of(bob!()), 0);
+            assert_eq!(nft.tournament(0).unwrap().entrants, vec![seed!(0)]);
+            assert_eq!(
+                nft.tournament(0).unwrap().registrants,
+                vec![(seed!(0), bob!())]
+            );
         }
 
         #[ink::test]
-        fn tokens_of() {
+        fn prize_goes_to_registrant() {
             let mut nft = PokeNFT::new();
+            nft.create_tournament(10, 8).unwrap();
             nft.mint(seed!(0)).unwrap();
-            nft.mint(seed!(1)).unwrap();
+            nft.set_user(seed!(0), Some(bob!()), 10).unwrap();
 
-            let empty: Vec<Seed> = vec![];
+            use_account!(bob!(), 10);
+            nft.register(0, seed!(0)).unwrap();
 
-            assert_eq!(nft.tokens_of(alice!()), vec![seed!(0), seed!(1)]);
-            assert_eq!(nft.tokens_of(bob!()), empty);
+            // the owner rents the token to someone else before the tournament ends
+            use_account!(alice!());
+            nft.set_user(seed!(0), Some(charlie!()), 20).unwrap();
 
-            nft.transfer(bob!(), seed!(0)).unwrap();
+            set_balance(contract_id(), 10);
+            let bob_balance = account_balance(bob!());
+            let charlie_balance = account_balance(charlie!());
 
-            assert_eq!(nft.tokens_of(alice!()), vec![seed!(1)]);
-            assert_eq!(nft.tokens_of(bob!()), vec![seed!(0)]);
+            nft.advance_round(0).unwrap();
+
+            assert_eq!(account_balance(bob!()), bob_balance + 10);
+            assert_eq!(account_balance(charlie!()), charlie_balance);
         }
 
         #[ink::test]
-        fn owner_of() {
+        fn offer_loan() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
             nft.mint(seed!(1)).unwrap();
 
-            assert_eq!(nft.owner_of(seed!(0)), alice!());
-            assert_eq!(nft.owner_of(seed!(1)), alice!());
-            assert_eq!(nft.owner_of(seed!(2)), zero_account!());
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
-            nft.transfer(bob!(), seed!(0)).unwrap();
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            assert_eq!(nft.loan_offers(), vec![seed!(0)]);
+            assert_eq!(
+                nft.loan_of(seed!(0)),
+                Some(Loan {
+                    borrower: alice!(),
+                    lender: None,
+                    principal: 100,
+                    interest: 10,
+                    duration: 50,
+                    due: 0,
+                })
+            );
+            assert_eq!(nft.loan_of(seed!(1)), None);
 
-            assert_eq!(nft.owner_of(seed!(0)), bob!());
-            assert_eq!(nft.owner_of(seed!(1)), alice!());
-            assert_eq!(nft.owner_of(seed!(2)), zero_account!());
+            if let Event::LoanOffered(LoanOffered {
+                seed,
+                borrower,
+                principal,
+                interest,
+                duration,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(borrower, alice!());
+                assert_eq!(principal, 100);
+                assert_eq!(interest, 10);
+                assert_eq!(duration, 50);
+            } else {
+                panic!("Expected to find LoanOffered event");
+            };
         }
 
         #[ink::test]
-        fn pokemon_of() {
+        fn offer_loan_with_invalid_terms() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
-            nft.mint(seed!(1)).unwrap();
 
-            assert_eq!(nft.pokemon_of(seed!(0)), 72);
-            assert_eq!(nft.pokemon_of(seed!(1)), 19);
-            assert_eq!(nft.pokemon_of(seed!(2)), 0);
-        }
+            assert_eq!(
+                nft.offer_loan(seed!(0), 0, 10, 50),
+                Err(Error::InvalidPrice)
+            );
+            assert_eq!(
+                nft.offer_loan(seed!(0), Balance::MAX, 1, 50),
+                Err(Error::InvalidPrice)
+            );
+            assert_eq!(
+                nft.offer_loan(seed!(0), 100, 10, 0),
+                Err(Error::InvalidDeadline)
+            );
 
-        type Event = <PokeNFT as ::ink_lang::BaseEvent>::Type;
+            advance_blocks!(1);
+            assert_eq!(
+                nft.offer_loan(seed!(0), 100, 10, BlockNumber::MAX),
+                Err(Error::InvalidDeadline)
+            );
+            assert_eq!(
+                nft.offer_loan(seed!(1), 100, 10, 50),
+                Err(Error::TokenNotFound)
+            );
+
+            use_account!(bob!());
+            assert_eq!(nft.offer_loan(seed!(0), 100, 10, 50), Err(Error::NotOwner));
+        }
 
         #[ink::test]
-        fn approved() {
+        fn offer_loan_twice() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
-            assert_eq!(nft.get_approved(seed!(0)), None);
-
-            nft.approve(bob!(), seed!(0)).unwrap();
-
-            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
-
-            if let Event::Approval(Approval { seed, account }) = get_event(1) {
-                assert_eq!(seed, seed!(0));
-                assert_eq!(account, Some(bob!()));
-            } else {
-                panic!("Expected last event to be an Approval");
-            }
+            assert_eq!(nft.offer_loan(seed!(0), 100, 0, 50), Err(Error::LoanExists));
+            assert_eq!(nft.loan_of(seed!(0)).unwrap().interest, 10);
         }
 
         #[ink::test]
-        fn approve_zero_address() {
+        fn fund_loan_with_changed_terms() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
+            // the borrower swaps the offer for one without interest before the lender funds it
+            nft.cancel_loan_offer(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 0, 1_000).unwrap();
+
+            use_account!(bob!(), 100);
             assert_eq!(
-                nft.approve(zero_account!(), seed!(0)),
-                Err(Error::InvalidAddress)
+                nft.fund_loan(seed!(0), 10, 50),
+                Err(Error::LoanTermsChanged)
             );
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            assert_eq!(nft.loan_of(seed!(0)).unwrap().lender, None);
         }
 
         #[ink::test]
-        fn approve_while_not_owner() {
+        fn cancel_loan_offer() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
             use_account!(bob!());
+            assert_eq!(nft.cancel_loan_offer(seed!(0)), Err(Error::NotAllowed));
 
-            assert_eq!(nft.approve(bob!(), seed!(0)), Err(Error::NotAllowed));
+            use_account!(alice!());
+            nft.cancel_loan_offer(seed!(0)).unwrap();
+
+            assert_eq!(nft.loan_of(seed!(0)), None);
+            assert_eq!(nft.loan_offers(), Vec::<Seed>::new());
+            assert_eq!(nft.cancel_loan_offer(seed!(0)), Err(Error::LoanNotFound));
+
+            if let Event::LoanCancelled(LoanCancelled { seed }) = last_event() {
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find LoanCancelled event");
+            };
         }
 
         #[ink::test]
-        fn approve_non_existing_token() {
+        fn fund_loan() {
             let mut nft = PokeNFT::new();
-            assert_eq!(nft.get_approved(seed!(0)), None);
+            nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
-            let result = nft.approve(bob!(), seed!(0));
+            advance_blocks!(5);
 
-            assert_eq!(result, Err(Error::TokenNotFound));
+            set_balance(contract_id(), 100);
+            let borrower_balance = account_balance(alice!());
+
+            use_account!(bob!(), 99);
+            assert_eq!(nft.fund_loan(seed!(0), 10, 50), Err(Error::InvalidPrice));
+
+            use_account!(alice!(), 100);
+            assert_eq!(nft.fund_loan(seed!(0), 10, 50), Err(Error::NotAllowed));
+
+            use_account!(bob!(), 100);
+            nft.fund_loan(seed!(0), 10, 50).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), contract_id());
+            assert_eq!(account_balance(alice!()), borrower_balance + 100);
+            assert_eq!(nft.loan_offers(), Vec::<Seed>::new());
+            assert_eq!(nft.loan_of(seed!(0)).unwrap().lender, Some(bob!()));
+            assert_eq!(nft.loan_of(seed!(0)).unwrap().due, 55);
+
+            use_account!(charlie!(), 100);
+            assert_eq!(nft.fund_loan(seed!(0), 10, 50), Err(Error::LoanFunded));
+
+            use_account!(alice!());
+            assert_eq!(nft.cancel_loan_offer(seed!(0)), Err(Error::LoanFunded));
+            assert_eq!(nft.transfer(bob!(), seed!(0)), Err(Error::NotAllowed));
+
+            if let Event::LoanFunded(LoanFunded { seed, lender, due }) = last_event() {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(lender, bob!());
+                assert_eq!(due, 55);
+            } else {
+                panic!("Expected to find LoanFunded event");
+            };
+
+            // the offer is funded, not cancelled
+            if let Event::Transfer(Transfer { to, .. }) = get_event(recorded_events().count() - 2) {
+                assert_eq!(to, Some(contract_id()));
+            } else {
+                panic!("Expected to find Transfer event");
+            };
         }
 
         #[ink::test]
-        fn approve_while_approved() {
+        fn transfer_drops_loan_offer() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
-            nft.approve(bob!(), seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
+            nft.transfer(charlie!(), seed!(0)).unwrap();
 
-            use_account!(bob!());
-            let result = nft.approve(alice!(), seed!(0));
+            assert_eq!(nft.loan_of(seed!(0)), None);
+            assert_eq!(nft.loan_offers(), Vec::<Seed>::new());
 
-            assert_eq!(result, Ok(()));
+            if let Event::LoanCancelled(LoanCancelled { seed }) =
+                get_event(recorded_events().count() - 2)
+            {
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find LoanCancelled event");
+            };
+
+            use_account!(bob!(), 100);
+            assert_eq!(nft.fund_loan(seed!(0), 10, 50), Err(Error::LoanNotFound));
+            assert_eq!(nft.owner_of(seed!(0)), charlie!());
         }
 
         #[ink::test]
-        fn clear_approval() {
+        fn burn_drops_loan_offer() {
             let mut nft = PokeNFT::new();
-
             nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
+            nft.burn(seed!(0)).unwrap();
 
-            nft.approve(bob!(), seed!(0)).unwrap();
-            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
-
-            nft.clear_approval(seed!(0)).unwrap();
+            assert_eq!(nft.loan_of(seed!(0)), None);
 
-            assert_eq!(nft.get_approved(seed!(0)), None);
+            if let Event::LoanCancelled(LoanCancelled { seed }) =
+                get_event(recorded_events().count() - 2)
+            {
+                assert_eq!(seed, seed!(0));
+            } else {
+                panic!("Expected to find LoanCancelled event");
+            };
         }
 
         #[ink::test]
-        fn approver_removes_itself() {
+        fn fund_loan_while_locked() {
             let mut nft = PokeNFT::new();
-
             nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
+            nft.lock(seed!(0), LockReason::Custom(0), 10).unwrap();
 
-            nft.approve(bob!(), seed!(0)).unwrap();
-            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
-
-            use_account!(bob!());
-            nft.clear_approval(seed!(0)).unwrap();
-
-            assert_eq!(nft.get_approved(seed!(0)), None);
+            use_account!(bob!(), 100);
+            assert_eq!(nft.fund_loan(seed!(0), 10, 50), Err(Error::TokenLocked));
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
         }
 
         #[ink::test]
-        fn other_user_fails_to_remove_approver() {
+        fn repay_loan() {
             let mut nft = PokeNFT::new();
-
             nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
-            nft.approve(bob!(), seed!(0)).unwrap();
-            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+            use_account!(alice!(), 110);
+            assert_eq!(nft.repay_loan(seed!(0)), Err(Error::LoanNotFunded));
 
-            use_account!(charlie!());
-            let result = nft.clear_approval(seed!(0));
+            set_balance(contract_id(), 100);
+            use_account!(bob!(), 100);
+            nft.fund_loan(seed!(0), 10, 50).unwrap();
 
-            assert_eq!(result, Err(Error::NotAllowed));
-            assert_eq!(nft.get_approved(seed!(0)), Some(bob!()));
+            advance_blocks!(50);
+
+            set_balance(contract_id(), 110);
+            let lender_balance = account_balance(bob!());
+
+            use_account!(alice!(), 100);
+            assert_eq!(nft.repay_loan(seed!(0)), Err(Error::InvalidPrice));
+
+            use_account!(charlie!(), 110);
+            assert_eq!(nft.repay_loan(seed!(0)), Err(Error::NotAllowed));
+
+            use_account!(alice!(), 110);
+            nft.repay_loan(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), alice!());
+            assert_eq!(nft.loan_of(seed!(0)), None);
+            assert_eq!(account_balance(bob!()), lender_balance + 110);
+
+            if let Event::LoanRepaid(LoanRepaid {
+                seed,
+                borrower,
+                lender,
+                amount,
+            }) = last_event()
+            {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(borrower, alice!());
+                assert_eq!(lender, bob!());
+                assert_eq!(amount, 110);
+            } else {
+                panic!("Expected to find LoanRepaid event");
+            };
         }
 
         #[ink::test]
-        fn approved_for_all() {
+        fn repay_loan_after_default() {
             let mut nft = PokeNFT::new();
+            nft.mint(seed!(0)).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
-            assert_eq!(nft.is_approved_for_all(alice!(), bob!()), false);
+            set_balance(contract_id(), 100);
+            use_account!(bob!(), 100);
+            nft.fund_loan(seed!(0), 10, 50).unwrap();
 
-            nft.set_approval_for_all(bob!(), true).unwrap();
+            advance_blocks!(51);
 
-            assert_eq!(nft.is_approved_for_all(alice!(), bob!()), true);
+            use_account!(alice!(), 110);
+            assert_eq!(nft.repay_loan(seed!(0)), Err(Error::LoanDefaulted));
         }
 
         #[ink::test]
-        fn operator_can_transfer_from_owner() {
+        fn claim_collateral() {
             let mut nft = PokeNFT::new();
             nft.mint(seed!(0)).unwrap();
-            nft.set_approval_for_all(bob!(), true).unwrap();
+            nft.offer_loan(seed!(0), 100, 10, 50).unwrap();
 
-            use_account!(bob!());
-            nft.transfer_from(alice!(), bob!(), seed!(0)).unwrap();
+            use_account!(bob!(), 0);
+            assert_eq!(nft.claim_collateral(seed!(0)), Err(Error::LoanNotFunded));
+
+            set_balance(contract_id(), 100);
+            use_account!(bob!(), 100);
+            nft.fund_loan(seed!(0), 10, 50).unwrap();
+
+            advance_blocks!(50);
+
+            use_account!(bob!(), 0);
+            assert_eq!(nft.claim_collateral(seed!(0)), Err(Error::LoanNotDefaulted));
+
+            advance_blocks!(1);
+
+            use_account!(alice!(), 0);
+            assert_eq!(nft.claim_collateral(seed!(0)), Err(Error::NotAllowed));
+
+            use_account!(bob!(), 0);
+            nft.claim_collateral(seed!(0)).unwrap();
+
+            assert_eq!(nft.owner_of(seed!(0)), bob!());
+            assert_eq!(nft.loan_of(seed!(0)), None);
+            assert_eq!(nft.claim_collateral(seed!(0)), Err(Error::LoanNotFound));
+
+            if let Event::CollateralClaimed(CollateralClaimed { seed, lender }) = last_event() {
+                assert_eq!(seed, seed!(0));
+                assert_eq!(lender, bob!());
+            } else {
+                panic!("Expected to find CollateralClaimed event");
+            };
         }
     }
 }
@@ -0,0 +1,284 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+// holds PokeNFT tokens on behalf of their owners. an example of another contract using PokeNFT
+// through the reference its `ink-as-dependency` feature generates.
+// owners need to approve the vault for a token before depositing it
+#[ink::contract]
+mod vault {
+    use ink_env::call::FromAccountId;
+    use ink_storage::collections::HashMap;
+    use pokenft::{PokeNFT, PokeNftInterface, PokemonId, Seed};
+
+    // the calls the vault makes to PokeNFT. ink can't run cross-contract calls in off-chain tests,
+    // so the vault's own checks are written against this trait and tested with a mock
+    pub trait Nft {
+        fn owner_of(&self, seed: Seed) -> AccountId;
+        fn pokemon_of(&self, seed: Seed) -> PokemonId;
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, seed: Seed) -> Result<()>;
+    }
+
+    impl Nft for PokeNFT {
+        fn owner_of(&self, seed: Seed) -> AccountId {
+            PokeNftInterface::owner_of(self, seed)
+        }
+
+        fn pokemon_of(&self, seed: Seed) -> PokemonId {
+            PokeNftInterface::pokemon_of(self, seed)
+        }
+
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, seed: Seed) -> Result<()> {
+            PokeNftInterface::transfer_from(self, from, to, seed).map_err(Error::Nft)
+        }
+    }
+
+    type Deposits = HashMap<Seed, (AccountId, PokemonId)>;
+
+    #[ink(storage)]
+    pub struct Vault {
+        nft: PokeNFT,
+        deposits: Deposits,
+    }
+
+    #[derive(Debug, scale::Encode, scale::Decode, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        NotOwner,
+        NotDeposited,
+        Nft(pokenft::Error),
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(event)]
+    pub struct Deposited {
+        seed: Seed,
+        owner: AccountId,
+        pokemon: PokemonId,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        seed: Seed,
+        owner: AccountId,
+    }
+
+    impl Vault {
+        #[ink(constructor)]
+        pub fn new(nft: AccountId) -> Self {
+            Self {
+                nft: FromAccountId::from_account_id(nft),
+                deposits: Default::default(),
+            }
+        }
+
+        #[ink(message)]
+        pub fn deposit(&mut self, seed: Seed) -> Result<()> {
+            let owner = self.env().caller();
+            let vault = self.env().account_id();
+            let pokemon = impl_deposit(&mut self.nft, &mut self.deposits, owner, vault, seed)?;
+
+            self.env().emit_event(Deposited {
+                seed,
+                owner,
+                pokemon,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, seed: Seed) -> Result<()> {
+            let owner = self.env().caller();
+            let vault = self.env().account_id();
+            impl_withdraw(&mut self.nft, &mut self.deposits, owner, vault, seed)?;
+
+            self.env().emit_event(Withdrawn { seed, owner });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn deposit_of(&self, seed: Seed) -> Option<(AccountId, PokemonId)> {
+            self.deposits.get(&seed).cloned()
+        }
+    }
+
+    fn impl_deposit<N: Nft>(
+        nft: &mut N,
+        deposits: &mut Deposits,
+        owner: AccountId,
+        vault: AccountId,
+        seed: Seed,
+    ) -> Result<PokemonId> {
+        if nft.owner_of(seed) != owner {
+            return Err(Error::NotOwner);
+        }
+
+        let pokemon = nft.pokemon_of(seed);
+        nft.transfer_from(owner, vault, seed)?;
+
+        deposits.insert(seed, (owner, pokemon));
+
+        Ok(pokemon)
+    }
+
+    fn impl_withdraw<N: Nft>(
+        nft: &mut N,
+        deposits: &mut Deposits,
+        owner: AccountId,
+        vault: AccountId,
+        seed: Seed,
+    ) -> Result<()> {
+        let (depositor, _) = deposits.get(&seed).cloned().ok_or(Error::NotDeposited)?;
+
+        if depositor != owner {
+            return Err(Error::NotOwner);
+        }
+
+        nft.transfer_from(vault, owner, seed)?;
+
+        deposits.take(&seed);
+
+        Ok(())
+    }
+
+    // cross-contract calls aren't supported by the off-chain environment, so the deposit and
+    // withdraw logic runs against `MockNft` instead of a deployed PokeNFT
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use ink_lang as ink;
+        use std::collections::BTreeMap;
+
+        const SEED: Seed = [0x0; 32];
+
+        fn alice() -> AccountId {
+            AccountId::from([0x1; 32])
+        }
+
+        fn bob() -> AccountId {
+            AccountId::from([0x2; 32])
+        }
+
+        fn vault() -> AccountId {
+            AccountId::from([0x9; 32])
+        }
+
+        #[derive(Default)]
+        struct MockNft {
+            tokens: BTreeMap<Seed, (AccountId, PokemonId)>,
+        }
+
+        impl Nft for MockNft {
+            fn owner_of(&self, seed: Seed) -> AccountId {
+                self.tokens
+                    .get(&seed)
+                    .map_or(AccountId::from([0x0; 32]), |(owner, _)| *owner)
+            }
+
+            fn pokemon_of(&self, seed: Seed) -> PokemonId {
+                self.tokens.get(&seed).map_or(0, |(_, pokemon)| *pokemon)
+            }
+
+            fn transfer_from(&mut self, from: AccountId, to: AccountId, seed: Seed) -> Result<()> {
+                match self.tokens.get_mut(&seed) {
+                    Some((owner, _)) if *owner == from => {
+                        *owner = to;
+                        Ok(())
+                    }
+                    Some(_) => Err(Error::Nft(pokenft::Error::NotOwner)),
+                    None => Err(Error::Nft(pokenft::Error::TokenNotFound)),
+                }
+            }
+        }
+
+        // alice owns a Pikachu
+        fn mock_nft() -> MockNft {
+            let mut nft = MockNft::default();
+            nft.tokens.insert(SEED, (alice(), 25));
+            nft
+        }
+
+        #[ink::test]
+        fn new() {
+            let vault = Vault::new(AccountId::from([0x1; 32]));
+
+            assert_eq!(vault.deposit_of([0x0; 32]), None);
+        }
+
+        #[ink::test]
+        fn withdraw_without_deposit() {
+            let mut vault = Vault::new(AccountId::from([0x1; 32]));
+
+            assert_eq!(vault.withdraw([0x0; 32]), Err(Error::NotDeposited));
+        }
+
+        #[ink::test]
+        fn deposit_and_withdraw() {
+            let mut nft = mock_nft();
+            let mut deposits = Deposits::default();
+
+            assert_eq!(
+                impl_deposit(&mut nft, &mut deposits, alice(), vault(), SEED),
+                Ok(25)
+            );
+            assert_eq!(nft.owner_of(SEED), vault());
+            assert_eq!(deposits.get(&SEED), Some(&(alice(), 25)));
+
+            assert_eq!(
+                impl_withdraw(&mut nft, &mut deposits, alice(), vault(), SEED),
+                Ok(())
+            );
+            assert_eq!(nft.owner_of(SEED), alice());
+            assert_eq!(deposits.get(&SEED), None);
+        }
+
+        #[ink::test]
+        fn deposit_someone_elses_token() {
+            let mut nft = mock_nft();
+            let mut deposits = Deposits::default();
+
+            assert_eq!(
+                impl_deposit(&mut nft, &mut deposits, bob(), vault(), SEED),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(
+                impl_deposit(&mut nft, &mut deposits, bob(), vault(), [0x1; 32]),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(nft.owner_of(SEED), alice());
+            assert_eq!(deposits.get(&SEED), None);
+        }
+
+        #[ink::test]
+        fn withdraw_someone_elses_deposit() {
+            let mut nft = mock_nft();
+            let mut deposits = Deposits::default();
+            impl_deposit(&mut nft, &mut deposits, alice(), vault(), SEED).unwrap();
+
+            assert_eq!(
+                impl_withdraw(&mut nft, &mut deposits, bob(), vault(), SEED),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(nft.owner_of(SEED), vault());
+            assert_eq!(deposits.get(&SEED), Some(&(alice(), 25)));
+        }
+
+        #[ink::test]
+        fn withdraw_when_transfer_fails() {
+            let mut nft = mock_nft();
+            let mut deposits = Deposits::default();
+            // a deposit for a token the vault doesn't hold
+            deposits.insert(SEED, (alice(), 25));
+
+            assert_eq!(
+                impl_withdraw(&mut nft, &mut deposits, alice(), vault(), SEED),
+                Err(Error::Nft(pokenft::Error::NotOwner))
+            );
+            assert_eq!(deposits.get(&SEED), Some(&(alice(), 25)));
+        }
+    }
+}